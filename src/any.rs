@@ -0,0 +1,117 @@
+use std::any::{Any, TypeId};
+
+use super::StackPtr;
+
+impl<'a> StackPtr<'a, dyn Any> {
+    /// Attempts to downcast a type-erased `StackPtr` to a concrete type. Returns the original `StackPtr` in `Err` if the value isn't a `T`.
+    pub fn downcast<T: Any>(sp: StackPtr<'a, dyn Any>) -> Result<StackPtr<'a, T>, StackPtr<'a, dyn Any>> {
+        if Any::type_id(&*sp) == TypeId::of::<T>() {
+            unsafe {
+                let ptr = StackPtr::into_mut(sp) as *mut dyn Any as *mut T;
+                Ok(StackPtr::from_mut(&mut *ptr))
+            }
+        } else {
+            Err(sp)
+        }
+    }
+
+    /// Converts a type-erased `StackPtr` to a concrete type without checking its `TypeId`, for hot paths where the type is already known. The type is still checked in debug builds.
+    ///
+    /// # Safety
+    ///
+    /// The value must actually be a `T`. Otherwise the returned `StackPtr` reads and eventually drops the value as the wrong type, which is undefined behavior.
+    pub unsafe fn downcast_unchecked<T: Any>(sp: StackPtr<'a, dyn Any>) -> StackPtr<'a, T> {
+        debug_assert!(Any::type_id(&*sp) == TypeId::of::<T>(), "downcast_unchecked to the wrong type");
+        let ptr = StackPtr::into_mut(sp) as *mut dyn Any as *mut T;
+        StackPtr::from_mut(&mut *ptr)
+    }
+}
+
+impl<'a, T: Any> StackPtr<'a, T> {
+    /// Erases the type of the pointee, turning a `StackPtr<T>` into a `StackPtr<dyn Any>`. The fat pointer comes from an ordinary reference coercion, so its vtable is always the one for `T`. Use `StackPtr::downcast` to get the concrete type back.
+    pub fn into_any(sp: StackPtr<'a, T>) -> StackPtr<'a, dyn Any> {
+        unsafe {
+            StackPtr::from_mut(StackPtr::into_mut(sp) as &mut dyn Any)
+        }
+    }
+}
+
+/// A fixed-capacity map holding at most one stack-allocated value per type, keyed by `TypeId`. Values are stored as `StackPtr<dyn Any>` in an inline array of `N` slots, so the map itself needs no heap allocation either.
+pub struct StackAnyMap<'a, const N: usize> {
+    entries: [Option<StackPtr<'a, dyn Any>>; N],
+}
+
+impl<'a, const N: usize> StackAnyMap<'a, N> {
+    /// Creates an empty map.
+    pub fn new() -> StackAnyMap<'a, N> {
+        StackAnyMap {
+            entries: [const { None }; N],
+        }
+    }
+
+    /// Returns the number of values in the map.
+    pub fn len(&self) -> usize {
+        self.entries.iter().filter(|entry| entry.is_some()).count()
+    }
+
+    /// Returns `true` if the map contains no values.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    fn position<T: Any>(&self) -> Option<usize> {
+        self.entries.iter().position(|entry| {
+            entry.as_ref().is_some_and(|sp| Any::type_id(&**sp) == TypeId::of::<T>())
+        })
+    }
+
+    /// Inserts a value, keyed by its type. If the map already held a value of type `T`, it is replaced and returned in `Ok(Some(_))`. If the map is full, the value is handed back in `Err`.
+    pub fn insert<T: Any>(&mut self, sp: StackPtr<'a, T>) -> Result<Option<StackPtr<'a, T>>, StackPtr<'a, T>> {
+        let index = match self.position::<T>() {
+            Some(index) => index,
+            None => match self.entries.iter().position(|entry| entry.is_none()) {
+                Some(index) => index,
+                None => return Err(sp),
+            },
+        };
+
+        let old = self.entries[index].replace(StackPtr::into_any(sp));
+        Ok(old.map(|old| {
+            StackPtr::downcast(old).unwrap_or_else(|_| unreachable!())
+        }))
+    }
+
+    /// Returns a reference to the value of type `T`, if there is one.
+    pub fn get<T: Any>(&self) -> Option<&T> {
+        self.position::<T>().map(|index| {
+            let sp = self.entries[index].as_ref().unwrap();
+            unsafe {
+                &*(&**sp as *const dyn Any as *const T)
+            }
+        })
+    }
+
+    /// Returns a mutable reference to the value of type `T`, if there is one.
+    pub fn get_mut<T: Any>(&mut self) -> Option<&mut T> {
+        self.position::<T>().map(move |index| {
+            let sp = self.entries[index].as_mut().unwrap();
+            unsafe {
+                &mut *(&mut **sp as *mut dyn Any as *mut T)
+            }
+        })
+    }
+
+    /// Removes the value of type `T` from the map and returns ownership of it.
+    pub fn remove<T: Any>(&mut self) -> Option<StackPtr<'a, T>> {
+        self.position::<T>().map(|index| {
+            let sp = self.entries[index].take().unwrap();
+            StackPtr::downcast(sp).unwrap_or_else(|_| unreachable!())
+        })
+    }
+}
+
+impl<'a, const N: usize> Default for StackAnyMap<'a, N> {
+    fn default() -> StackAnyMap<'a, N> {
+        StackAnyMap::new()
+    }
+}
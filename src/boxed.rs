@@ -0,0 +1,116 @@
+use std::fmt::{Debug, Formatter};
+use std::fmt;
+use std::mem::MaybeUninit;
+use std::ops::{Deref, DerefMut};
+use std::sync::Arc;
+use std::{ptr, slice};
+
+use super::StackPtr;
+use super::iter::SliceIntoIter;
+
+/// An owned slice on the stack, for code that would otherwise use `Box<[T]>`. It is a thin wrapper around `StackPtr<[T]>` with a name that states the intent, and it dereferences to `[T]`, so `len`, `iter` and the rest of the slice API work just like they do on a boxed slice.
+pub struct StackBoxedSlice<'a, T: 'a> {
+    inner: StackPtr<'a, [T]>,
+}
+
+impl<'a, T> StackBoxedSlice<'a, T> {
+    /// Wraps an owned stack slice.
+    pub fn from_stackptr(sp: StackPtr<'a, [T]>) -> StackBoxedSlice<'a, T> {
+        StackBoxedSlice { inner: sp }
+    }
+
+    /// Unwraps the underlying `StackPtr`.
+    pub fn into_stackptr(self) -> StackPtr<'a, [T]> {
+        self.inner
+    }
+
+    /// Moves the elements of a heap-allocated boxed slice into `buf`, freeing the heap allocation. Only the first `boxed.len()` slots of `buf` are used.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `buf` is shorter than `boxed`. The boxed slice is dropped normally in that case.
+    pub fn from_boxed_slice(boxed: Box<[T]>, buf: &'a mut [MaybeUninit<T>]) -> StackBoxedSlice<'a, T> {
+        let len = boxed.len();
+        assert!(len <= buf.len(), "boxed slice length {} exceeds buffer capacity {}", len, buf.len());
+
+        let mut vec = boxed.into_vec();
+        let dst = buf.as_mut_ptr() as *mut T;
+        unsafe {
+            ptr::copy_nonoverlapping(vec.as_ptr(), dst, len);
+            vec.set_len(0);
+            StackBoxedSlice::from_stackptr(StackPtr::from_mut(slice::from_raw_parts_mut(dst, len)))
+        }
+    }
+
+    /// Moves the elements onto the heap, spilling the stack slice into a `Box<[T]>` for APIs that require one.
+    pub fn into_boxed_slice(self) -> Box<[T]> {
+        self.into_iter().collect()
+    }
+}
+
+impl<'a, T> StackPtr<'a, T> {
+    /// Moves the value onto the heap into an `Arc`, for sharing data that was built on the stack, e.g. with other threads. The value is moved rather than cloned, and the storage is left logically uninitialized.
+    pub fn spill_to_arc(sp: StackPtr<'a, T>) -> Arc<T> {
+        Arc::new(unsafe { ptr::read(StackPtr::into_mut(sp)) })
+    }
+}
+
+impl<'a, T> StackPtr<'a, [T]> {
+    /// Moves the elements onto the heap into an `Arc<[T]>`, the slice counterpart of `spill_to_arc`. Each element is moved, not cloned.
+    pub fn spill_slice_to_arc(sp: StackPtr<'a, [T]>) -> Arc<[T]> {
+        sp.into_iter().collect()
+    }
+}
+
+impl<'a, T> From<StackPtr<'a, [T]>> for StackBoxedSlice<'a, T> {
+    fn from(sp: StackPtr<'a, [T]>) -> StackBoxedSlice<'a, T> {
+        StackBoxedSlice::from_stackptr(sp)
+    }
+}
+
+impl<'a, T> Deref for StackBoxedSlice<'a, T> {
+    type Target = [T];
+
+    fn deref(&self) -> &[T] {
+        &self.inner
+    }
+}
+
+impl<'a, T> DerefMut for StackBoxedSlice<'a, T> {
+    fn deref_mut(&mut self) -> &mut [T] {
+        &mut self.inner
+    }
+}
+
+impl<'a, T> IntoIterator for StackBoxedSlice<'a, T> {
+    type Item = T;
+    type IntoIter = SliceIntoIter<'a, T>;
+
+    fn into_iter(self) -> SliceIntoIter<'a, T> {
+        self.inner.into_iter()
+    }
+}
+
+impl<'a, 'b, T> IntoIterator for &'b StackBoxedSlice<'a, T> {
+    type Item = &'b T;
+    type IntoIter = slice::Iter<'b, T>;
+
+    fn into_iter(self) -> slice::Iter<'b, T> {
+        self.iter()
+    }
+}
+
+impl<'a, 'b, T> IntoIterator for &'b mut StackBoxedSlice<'a, T> {
+    type Item = &'b mut T;
+    type IntoIter = slice::IterMut<'b, T>;
+
+    fn into_iter(self) -> slice::IterMut<'b, T> {
+        self.iter_mut()
+    }
+}
+
+impl<'a, T> Debug for StackBoxedSlice<'a, T> where T: Debug {
+    fn fmt(&self, formatter: &mut Formatter) -> fmt::Result {
+        self.deref().fmt(formatter)
+    }
+}
@@ -0,0 +1,50 @@
+use std::error::Error;
+use std::fmt::{Debug, Display};
+use std::io::{Read, Write};
+
+use super::StackPtr;
+
+// Stable conversions to the standard trait objects used most often: `Debug`, `Display`, `Error`, `Iterator`, `Read` and `Write`, plus `Any` in the `any` module. Each goes through an ordinary `&mut` reference coercion, so they are checked by the compiler just like `CoerceUnsized` is on nightly.
+impl<'a, T: 'a> StackPtr<'a, T> {
+    /// Converts into a `StackPtr<dyn Debug>`.
+    pub fn into_debug(sp: StackPtr<'a, T>) -> StackPtr<'a, dyn Debug + 'a> where T: Debug {
+        unsafe {
+            StackPtr::from_mut(StackPtr::into_mut(sp) as &mut (dyn Debug + 'a))
+        }
+    }
+
+    /// Converts into a `StackPtr<dyn Display>`.
+    pub fn into_display(sp: StackPtr<'a, T>) -> StackPtr<'a, dyn Display + 'a> where T: Display {
+        unsafe {
+            StackPtr::from_mut(StackPtr::into_mut(sp) as &mut (dyn Display + 'a))
+        }
+    }
+
+    /// Converts into a `StackPtr<dyn Error>`.
+    pub fn into_error(sp: StackPtr<'a, T>) -> StackPtr<'a, dyn Error + 'a> where T: Error {
+        unsafe {
+            StackPtr::from_mut(StackPtr::into_mut(sp) as &mut (dyn Error + 'a))
+        }
+    }
+
+    /// Converts into a `StackPtr<dyn Iterator>` with the same item type.
+    pub fn into_iterator(sp: StackPtr<'a, T>) -> StackPtr<'a, dyn Iterator<Item = T::Item> + 'a> where T: Iterator {
+        unsafe {
+            StackPtr::from_mut(StackPtr::into_mut(sp) as &mut (dyn Iterator<Item = T::Item> + 'a))
+        }
+    }
+
+    /// Converts into a `StackPtr<dyn Read>`.
+    pub fn into_read(sp: StackPtr<'a, T>) -> StackPtr<'a, dyn Read + 'a> where T: Read {
+        unsafe {
+            StackPtr::from_mut(StackPtr::into_mut(sp) as &mut (dyn Read + 'a))
+        }
+    }
+
+    /// Converts into a `StackPtr<dyn Write>`.
+    pub fn into_write(sp: StackPtr<'a, T>) -> StackPtr<'a, dyn Write + 'a> where T: Write {
+        unsafe {
+            StackPtr::from_mut(StackPtr::into_mut(sp) as &mut (dyn Write + 'a))
+        }
+    }
+}
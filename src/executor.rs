@@ -0,0 +1,20 @@
+use std::future::Future;
+use std::hint;
+use std::pin::Pin;
+use std::task::{Context, Poll, Waker};
+
+use super::StackPtr;
+
+/// Runs a stack-allocated future to completion on the current thread and returns its output, without any heap allocation. Requires the `executor` feature.
+///
+/// The future is polled in a loop with a waker that does nothing, spinning between polls, so this is only suitable for futures that make progress on their own, as in tests or simple embedded code. Pinning is sound because the `StackPtr` is owned by this function, which never moves the future and always drops it in place, even if polling panics.
+pub fn block_on_stack<'a, F: Future + ?Sized>(mut sp: StackPtr<'a, F>) -> F::Output {
+    let mut fut = unsafe { Pin::new_unchecked(&mut *sp) };
+    let mut cx = Context::from_waker(Waker::noop());
+    loop {
+        match fut.as_mut().poll(&mut cx) {
+            Poll::Ready(output) => return output,
+            Poll::Pending => hint::spin_loop(),
+        }
+    }
+}
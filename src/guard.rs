@@ -0,0 +1,62 @@
+use std::fmt::{Debug, Formatter};
+use std::fmt;
+use std::mem::ManuallyDrop;
+use std::ops::{Deref, DerefMut};
+use std::ptr;
+
+use super::StackPtr;
+
+/// A `StackPtr` with an action attached to its destruction, created by `StackPtr::on_drop`. When the guard is dropped, the closure is called with the pointee first, and the pointee itself is dropped afterwards. If the closure panics, the pointee is still dropped during unwinding.
+pub struct OnDrop<'a, T: 'a + ?Sized, F: FnOnce(&mut T)> {
+    sp: StackPtr<'a, T>,
+    f: Option<F>,
+}
+
+impl<'a, T: ?Sized, F: FnOnce(&mut T)> OnDrop<'a, T, F> {
+    /// Removes the guard without running the closure, returning the `StackPtr`.
+    pub fn into_inner(guard: OnDrop<'a, T, F>) -> StackPtr<'a, T> {
+        let mut guard = ManuallyDrop::new(guard);
+        drop(guard.f.take());
+        unsafe {
+            ptr::read(&guard.sp)
+        }
+    }
+}
+
+impl<'a, T: ?Sized, F: FnOnce(&mut T)> Drop for OnDrop<'a, T, F> {
+    fn drop(&mut self) {
+        if let Some(f) = self.f.take() {
+            f(&mut self.sp);
+        }
+    }
+}
+
+impl<'a, T: ?Sized, F: FnOnce(&mut T)> Deref for OnDrop<'a, T, F> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        &self.sp
+    }
+}
+
+impl<'a, T: ?Sized, F: FnOnce(&mut T)> DerefMut for OnDrop<'a, T, F> {
+    fn deref_mut(&mut self) -> &mut T {
+        &mut self.sp
+    }
+}
+
+impl<'a, T: ?Sized, F: FnOnce(&mut T)> Debug for OnDrop<'a, T, F> where T: Debug {
+    fn fmt(&self, formatter: &mut Formatter) -> fmt::Result {
+        formatter.debug_struct("OnDrop").field("value", &&*self.sp).finish_non_exhaustive()
+    }
+}
+
+impl<'a, T: ?Sized> StackPtr<'a, T> {
+    /// Attaches an action to be run when the pointee is dropped, for logging or cleanup tied to a stack-allocated resource. The returned guard dereferences to the pointee; when it is dropped, `f` runs first, with mutable access to the pointee, and then the pointee's own destructor runs.
+    pub fn on_drop<F: FnOnce(&mut T)>(sp: StackPtr<'a, T>, f: F) -> OnDrop<'a, T, F> {
+        OnDrop {
+            sp,
+            f: Some(f),
+        }
+    }
+}
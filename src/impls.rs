@@ -1,6 +1,14 @@
-use std::fmt::{Debug, Formatter};
+use std::error::Error;
+use std::fmt::{Debug, Display, Formatter};
 use std::fmt;
+use std::future::Future;
+use std::hash::Hasher;
+use std::io::{self, IoSlice, IoSliceMut, Read, Write};
 use std::ops::{Deref, DerefMut};
+#[cfg(unix)]
+use std::os::unix::io::{AsFd, AsRawFd, BorrowedFd, RawFd};
+use std::pin::Pin;
+use std::task::{Context, Poll};
 
 use super::StackPtr;
 
@@ -16,17 +24,213 @@ impl<'a, T: ?Sized> Debug for StackPtr<'a, T> where T: Debug {
     }
 }
 
+impl<'a, T: ?Sized> Display for StackPtr<'a, T> where T: Display {
+    fn fmt(&self, formatter: &mut Formatter) -> fmt::Result {
+        self.deref().fmt(formatter)
+    }
+}
+
+impl<'a, T: ?Sized> Error for StackPtr<'a, T> where T: Error {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        self.deref().source()
+    }
+}
+
+// A blanket `impl<I: Iterator + ?Sized> Iterator for StackPtr<I>` would conflict with the owning `IntoIterator` impl for `StackPtr<[T]>`, so only iterator trait objects are covered.
+macro_rules! impl_iterator_for_dyn {
+    ($($bounds:tt)*) => {
+        impl<'a, 'b, T> Iterator for StackPtr<'a, dyn Iterator<Item = T> $($bounds)* + 'b> {
+            type Item = T;
+
+            fn next(&mut self) -> Option<T> {
+                self.deref_mut().next()
+            }
+
+            fn size_hint(&self) -> (usize, Option<usize>) {
+                self.deref().size_hint()
+            }
+        }
+    };
+}
+
+impl_iterator_for_dyn!();
+impl_iterator_for_dyn!(+ Send);
+
+// Comparisons against plain slices and arrays, so that `assert_eq!(sp, [1, 2, 3])` works without dereferencing first. A blanket `impl<U> PartialEq<U> where [T]: PartialEq<U>` would leave out `&[U]` and `&[U; N]`, which `[T]` itself doesn't compare against.
+macro_rules! impl_slice_eq {
+    ([$($vars:tt)*] $rhs:ty) => {
+        impl<'a, $($vars)* T, U> PartialEq<$rhs> for StackPtr<'a, [T]> where T: PartialEq<U> {
+            fn eq(&self, other: &$rhs) -> bool {
+                self[..] == other[..]
+            }
+        }
+    };
+}
+
+impl_slice_eq!([] [U]);
+impl_slice_eq!(['b,] &'b [U]);
+impl_slice_eq!([const N: usize,] [U; N]);
+impl_slice_eq!(['b, const N: usize,] &'b [U; N]);
+
+impl<'a> PartialEq<str> for StackPtr<'a, str> {
+    fn eq(&self, other: &str) -> bool {
+        self[..] == other[..]
+    }
+}
+
+impl<'a, 'b> PartialEq<&'b str> for StackPtr<'a, str> {
+    fn eq(&self, other: &&'b str) -> bool {
+        self[..] == other[..]
+    }
+}
+
+impl<'a, R: ?Sized> Read for StackPtr<'a, R> where R: Read {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        self.deref_mut().read(buf)
+    }
+
+    fn read_vectored(&mut self, bufs: &mut [IoSliceMut]) -> io::Result<usize> {
+        self.deref_mut().read_vectored(bufs)
+    }
+
+    fn read_to_end(&mut self, buf: &mut Vec<u8>) -> io::Result<usize> {
+        self.deref_mut().read_to_end(buf)
+    }
+
+    fn read_to_string(&mut self, buf: &mut String) -> io::Result<usize> {
+        self.deref_mut().read_to_string(buf)
+    }
+
+    fn read_exact(&mut self, buf: &mut [u8]) -> io::Result<()> {
+        self.deref_mut().read_exact(buf)
+    }
+}
+
+impl<'a, W: ?Sized> Write for StackPtr<'a, W> where W: Write {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.deref_mut().write(buf)
+    }
+
+    fn write_vectored(&mut self, bufs: &[IoSlice]) -> io::Result<usize> {
+        self.deref_mut().write_vectored(bufs)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.deref_mut().flush()
+    }
+
+    fn write_all(&mut self, buf: &[u8]) -> io::Result<()> {
+        self.deref_mut().write_all(buf)
+    }
+
+    fn write_fmt(&mut self, fmt: fmt::Arguments) -> io::Result<()> {
+        self.deref_mut().write_fmt(fmt)
+    }
+}
+
+#[cfg(unix)]
+impl<'a, T: ?Sized> AsFd for StackPtr<'a, T> where T: AsFd {
+    fn as_fd(&self) -> BorrowedFd<'_> {
+        self.deref().as_fd()
+    }
+}
+
+#[cfg(unix)]
+impl<'a, T: ?Sized> AsRawFd for StackPtr<'a, T> where T: AsRawFd {
+    fn as_raw_fd(&self) -> RawFd {
+        self.deref().as_raw_fd()
+    }
+}
+
+impl<'a, H: ?Sized> Hasher for StackPtr<'a, H> where H: Hasher {
+    fn finish(&self) -> u64 {
+        self.deref().finish()
+    }
+
+    fn write(&mut self, bytes: &[u8]) {
+        self.deref_mut().write(bytes)
+    }
+
+    fn write_u8(&mut self, i: u8) {
+        self.deref_mut().write_u8(i)
+    }
+
+    fn write_u16(&mut self, i: u16) {
+        self.deref_mut().write_u16(i)
+    }
+
+    fn write_u32(&mut self, i: u32) {
+        self.deref_mut().write_u32(i)
+    }
+
+    fn write_u64(&mut self, i: u64) {
+        self.deref_mut().write_u64(i)
+    }
+
+    fn write_u128(&mut self, i: u128) {
+        self.deref_mut().write_u128(i)
+    }
+
+    fn write_usize(&mut self, i: usize) {
+        self.deref_mut().write_usize(i)
+    }
+
+    fn write_i8(&mut self, i: i8) {
+        self.deref_mut().write_i8(i)
+    }
+
+    fn write_i16(&mut self, i: i16) {
+        self.deref_mut().write_i16(i)
+    }
+
+    fn write_i32(&mut self, i: i32) {
+        self.deref_mut().write_i32(i)
+    }
+
+    fn write_i64(&mut self, i: i64) {
+        self.deref_mut().write_i64(i)
+    }
+
+    fn write_i128(&mut self, i: i128) {
+        self.deref_mut().write_i128(i)
+    }
+
+    fn write_isize(&mut self, i: isize) {
+        self.deref_mut().write_isize(i)
+    }
+}
+
+/// Like `Box`, a `StackPtr` can only be polled as a future if the pointee is `Unpin`, since polling through `&mut self` would otherwise let the future be moved after it has been pinned.
+impl<'a, F: ?Sized> Future for StackPtr<'a, F> where F: Future + Unpin {
+    type Output = F::Output;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context) -> Poll<F::Output> {
+        Pin::new(self.get_mut().deref_mut()).poll(cx)
+    }
+}
+
+/// Pinning an `Unpin` pointee is always safe, since `Unpin` types don't rely on staying in place. For other types, see `StackPtr::into_pin_unchecked`.
+impl<'a, T: ?Sized> From<StackPtr<'a, T>> for Pin<StackPtr<'a, T>> where T: Unpin {
+    fn from(sp: StackPtr<'a, T>) -> Pin<StackPtr<'a, T>> {
+        Pin::new(sp)
+    }
+}
+
 impl<'a, T: ?Sized> Deref for StackPtr<'a, T> {
     type Target = T;
 
     fn deref(&self) -> &T {
-        &self.ptr
+        unsafe {
+            self.ptr.as_ref()
+        }
     }
 }
 
 impl<'a, T: ?Sized> DerefMut for StackPtr<'a, T> {
     fn deref_mut(&mut self) -> &mut T {
-        self.ptr
+        unsafe {
+            self.ptr.as_mut()
+        }
     }
 }
 
@@ -1,14 +1,29 @@
+use std::convert::TryFrom;
+use std::iter::{Enumerate, Rev};
 use std::marker::PhantomData;
 use std::slice;
-use std::{ptr};
+use std::{mem, ptr};
 
 use super::StackPtr;
 
+/// An owning iterator over the elements of a `StackPtr<[T]>`, created by its `IntoIterator` impl. Each element is either moved out by `next` or dropped along with the iterator, so adapters like `find` or `position` that stop partway through don't leak or double-drop anything.
 pub struct SliceIntoIter<'a, T: 'a> {
     slice_iter: slice::IterMut<'a, T>,
     _marker: PhantomData<[T]>,
 }
 
+impl<'a, T> SliceIntoIter<'a, T> {
+    /// Returns a reference to the next element without consuming it. The peeked element is still yielded by the following call to `next`, or dropped along with the rest if the iterator is dropped first.
+    pub fn peek(&self) -> Option<&T> {
+        self.slice_iter.as_slice().first()
+    }
+
+    /// Returns the number of elements that haven't been yielded yet. These are the elements that would be dropped if the iterator were dropped now.
+    pub fn remaining(&self) -> usize {
+        self.slice_iter.len()
+    }
+}
+
 impl<'a, T> Drop for SliceIntoIter<'a, T> {
     fn drop(&mut self) {
         for ptr in &mut self.slice_iter {
@@ -29,16 +44,216 @@ impl<'a, T> Iterator for SliceIntoIter<'a, T> {
             }
         })
     }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.slice_iter.size_hint()
+    }
+}
+
+impl<'a, T> DoubleEndedIterator for SliceIntoIter<'a, T> {
+    fn next_back(&mut self) -> Option<T> {
+        self.slice_iter.next_back().map(|ptr| {
+            unsafe {
+                ptr::read(ptr)
+            }
+        })
+    }
 }
 
+impl<'a, T> ExactSizeIterator for SliceIntoIter<'a, T> {}
+
 impl<'a, T> IntoIterator for StackPtr<'a, [T]> {
     type Item = T;
     type IntoIter = SliceIntoIter<'a, T>;
 
     fn into_iter(self) -> Self::IntoIter {
         SliceIntoIter {
-            slice_iter: StackPtr::into_mut(self).into_iter(),
+            slice_iter: StackPtr::into_mut(self).iter_mut(),
+            _marker: PhantomData
+        }
+    }
+}
+
+impl<'a, T, const N: usize> IntoIterator for StackPtr<'a, [T; N]> {
+    type Item = T;
+    type IntoIter = SliceIntoIter<'a, T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        SliceIntoIter {
+            slice_iter: StackPtr::into_mut(self).iter_mut(),
             _marker: PhantomData
         }
     }
 }
+
+/// An owning iterator over `chunk_size` elements of a `StackPtr<[T]>` at a time, created by `StackPtr::into_chunks`. Each chunk is yielded as its own `StackPtr`, and any chunks that haven't been yielded are dropped along with the iterator.
+pub struct ChunksIntoIter<'a, T: 'a> {
+    rest: &'a mut [T],
+    chunk_size: usize,
+    _marker: PhantomData<[T]>,
+}
+
+impl<'a, T> Drop for ChunksIntoIter<'a, T> {
+    fn drop(&mut self) {
+        unsafe {
+            ptr::drop_in_place(self.rest)
+        }
+    }
+}
+
+impl<'a, T> Iterator for ChunksIntoIter<'a, T> {
+    type Item = StackPtr<'a, [T]>;
+
+    fn next(&mut self) -> Option<StackPtr<'a, [T]>> {
+        if self.rest.is_empty() {
+            return None;
+        }
+
+        let rest = mem::take(&mut self.rest);
+        let mid = self.chunk_size.min(rest.len());
+        let (chunk, rest) = rest.split_at_mut(mid);
+        self.rest = rest;
+        Some(unsafe { StackPtr::from_mut(chunk) })
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let len = self.rest.len().div_ceil(self.chunk_size);
+        (len, Some(len))
+    }
+}
+
+/// An owning iterator over `K` elements of a `StackPtr<[T]>` at a time, created by `StackPtr::into_array_chunks`. Each chunk is yielded as a `StackPtr<[T; K]>`. Any chunks that haven't been yielded are dropped along with the iterator, as are the leftover elements at the end that don't make up a full chunk.
+pub struct ArrayChunksIntoIter<'a, T: 'a, const K: usize> {
+    rest: &'a mut [T],
+    _marker: PhantomData<[T]>,
+}
+
+impl<'a, T, const K: usize> Drop for ArrayChunksIntoIter<'a, T, K> {
+    fn drop(&mut self) {
+        unsafe {
+            ptr::drop_in_place(self.rest)
+        }
+    }
+}
+
+impl<'a, T, const K: usize> Iterator for ArrayChunksIntoIter<'a, T, K> {
+    type Item = StackPtr<'a, [T; K]>;
+
+    fn next(&mut self) -> Option<StackPtr<'a, [T; K]>> {
+        if self.rest.len() < K {
+            return None;
+        }
+
+        let (chunk, rest) = mem::take(&mut self.rest).split_at_mut(K);
+        self.rest = rest;
+        let chunk = <&mut [T; K]>::try_from(chunk).unwrap();
+        Some(unsafe { StackPtr::from_mut(chunk) })
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let len = self.rest.len() / K;
+        (len, Some(len))
+    }
+}
+
+/// An owning iterator over the elements of a `StackPtr<[T]>` that yields each one as its own `StackPtr<T>` pointing into the original storage, created by `StackPtr::into_elem_ptrs`. Elements are only ever handed out from the ends of the remaining range, so the iterator always knows which ones it still owns: dropping it drops exactly the elements that haven't been yielded, whether or not the yielded sub-pointers are still alive.
+pub struct ElemPtrsIntoIter<'a, T: 'a> {
+    rest: &'a mut [T],
+    _marker: PhantomData<[T]>,
+}
+
+impl<'a, T> Drop for ElemPtrsIntoIter<'a, T> {
+    fn drop(&mut self) {
+        unsafe {
+            ptr::drop_in_place(self.rest)
+        }
+    }
+}
+
+impl<'a, T> Iterator for ElemPtrsIntoIter<'a, T> {
+    type Item = StackPtr<'a, T>;
+
+    fn next(&mut self) -> Option<StackPtr<'a, T>> {
+        let (first, rest) = mem::take(&mut self.rest).split_first_mut()?;
+        self.rest = rest;
+        Some(unsafe { StackPtr::from_mut(first) })
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.rest.len(), Some(self.rest.len()))
+    }
+}
+
+impl<'a, T> DoubleEndedIterator for ElemPtrsIntoIter<'a, T> {
+    fn next_back(&mut self) -> Option<StackPtr<'a, T>> {
+        let (last, rest) = mem::take(&mut self.rest).split_last_mut()?;
+        self.rest = rest;
+        Some(unsafe { StackPtr::from_mut(last) })
+    }
+}
+
+impl<'a, T> ExactSizeIterator for ElemPtrsIntoIter<'a, T> {}
+
+impl<'a, T> StackPtr<'a, [T]> {
+    /// Moves the elements out back to front, starting with the last one. Equivalent to `sp.into_iter().rev()`; elements that aren't yielded are dropped along with the iterator, as with `into_iter`.
+    pub fn into_iter_rev(sp: StackPtr<'a, [T]>) -> Rev<SliceIntoIter<'a, T>> {
+        sp.into_iter().rev()
+    }
+
+    /// Moves the elements out front to back, each paired with its index in the original slice. Equivalent to `sp.into_iter().enumerate()`; if the iterator is dropped early, the elements it hasn't yielded are dropped with it.
+    pub fn into_iter_indexed(sp: StackPtr<'a, [T]>) -> Enumerate<SliceIntoIter<'a, T>> {
+        sp.into_iter().enumerate()
+    }
+
+    /// Moves each element into `f` in order, threading an accumulator through, and returns the final accumulator. Equivalent to `sp.into_iter().fold(init, f)`. Every element is handed to `f`, so none are dropped here; if `f` panics, the elements it hasn't received yet are dropped during unwinding.
+    pub fn into_fold<B, F: FnMut(B, T) -> B>(sp: StackPtr<'a, [T]>, init: B, f: F) -> B {
+        sp.into_iter().fold(init, f)
+    }
+
+    /// Moves every element into one of two heap-allocated `Vec`s: the first gets the elements for which `pred` returns `true`, the second the rest, each in their original order. Equivalent to `sp.into_iter().partition(pred)`.
+    pub fn partition<F: FnMut(&T) -> bool>(sp: StackPtr<'a, [T]>, pred: F) -> (Vec<T>, Vec<T>) {
+        sp.into_iter().partition(pred)
+    }
+
+    /// Splits a `StackPtr<[T]>` into owned chunks of `chunk_size` elements, with the last chunk holding whatever is left over. Ownership is transferred chunk by chunk, so elements in chunks that are never yielded are dropped by the iterator.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `chunk_size` is 0.
+    pub fn into_chunks(sp: StackPtr<'a, [T]>, chunk_size: usize) -> ChunksIntoIter<'a, T> {
+        assert!(chunk_size != 0, "chunk size must be non-zero");
+        ChunksIntoIter {
+            rest: StackPtr::into_mut(sp),
+            chunk_size,
+            _marker: PhantomData,
+        }
+    }
+
+    /// Splits a `StackPtr<[T]>` into owned arrays of `K` elements, the owning counterpart of `<[T]>::array_chunks`. If the length isn't a multiple of `K`, the elements left over at the end are never yielded and are dropped by the iterator, along with any chunks that weren't taken.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `K` is 0.
+    pub fn into_array_chunks<const K: usize>(sp: StackPtr<'a, [T]>) -> ArrayChunksIntoIter<'a, T, K> {
+        assert!(K != 0, "chunk size must be non-zero");
+        ArrayChunksIntoIter {
+            rest: StackPtr::into_mut(sp),
+            _marker: PhantomData,
+        }
+    }
+
+    /// Splits a `StackPtr<[T]>` into one `StackPtr<T>` per element, lazily and front to back. Unlike `into_iter`, the elements aren't moved out: each sub-pointer keeps pointing into the original storage and drops its element when it goes out of scope. Elements that are never yielded are dropped by the iterator.
+    pub fn into_elem_ptrs(sp: StackPtr<'a, [T]>) -> ElemPtrsIntoIter<'a, T> {
+        ElemPtrsIntoIter {
+            rest: StackPtr::into_mut(sp),
+            _marker: PhantomData,
+        }
+    }
+}
+
+impl<'a, 'b, T: ?Sized> StackPtr<'a, [StackPtr<'b, T>]> {
+    /// Moves the inner `StackPtr`s out of a slice of them one at a time, for nested structures such as a slice of sub-pointers from `into_elem_ptrs`. This is `into_iter` under a more descriptive name: each inner `StackPtr` is yielded by value, and the ones that aren't yielded are dropped along with the iterator, which drops their pointees.
+    pub fn flatten(sp: StackPtr<'a, [StackPtr<'b, T>]>) -> SliceIntoIter<'a, StackPtr<'b, T>> {
+        sp.into_iter()
+    }
+}
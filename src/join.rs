@@ -0,0 +1,60 @@
+use std::array;
+use std::future::Future;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+
+/// A future that polls up to `N` pinned futures with the same output type concurrently and completes with all of their outputs, in order, once every one of them has completed. Nothing is allocated: the futures are borrowed, and the outputs are kept inline until all are ready. The `join_stack!` macro declares and pins the futures on the stack for you.
+pub struct StackJoin<'a, T, const N: usize> {
+    futures: [Pin<&'a mut (dyn Future<Output = T> + 'a)>; N],
+    outputs: [Option<T>; N],
+    done: bool,
+}
+
+impl<'a, T, const N: usize> StackJoin<'a, T, N> {
+    /// Creates a future that joins `futures`.
+    pub fn new(futures: [Pin<&'a mut (dyn Future<Output = T> + 'a)>; N]) -> StackJoin<'a, T, N> {
+        StackJoin {
+            futures,
+            outputs: array::from_fn(|_| None),
+            done: false,
+        }
+    }
+}
+
+// The futures are only ever accessed through their own `Pin`s, and the outputs are never pinned, so moving a `StackJoin` is fine.
+impl<'a, T, const N: usize> Unpin for StackJoin<'a, T, N> {}
+
+impl<'a, T, const N: usize> Future for StackJoin<'a, T, N> {
+    type Output = [T; N];
+
+    /// Polls every future that hasn't completed yet, in order.
+    ///
+    /// # Panics
+    ///
+    /// Panics if polled again after it has completed.
+    fn poll(self: Pin<&mut Self>, cx: &mut Context) -> Poll<[T; N]> {
+        let this = self.get_mut();
+        assert!(!this.done, "StackJoin polled after completion");
+
+        let mut pending = false;
+        for (fut, output) in this.futures.iter_mut().zip(this.outputs.iter_mut()) {
+            if output.is_none() {
+                match fut.as_mut().poll(cx) {
+                    Poll::Ready(value) => *output = Some(value),
+                    Poll::Pending => pending = true,
+                }
+            }
+        }
+        if pending {
+            return Poll::Pending;
+        }
+
+        this.done = true;
+        Poll::Ready(array::from_fn(|i| this.outputs[i].take().unwrap()))
+    }
+}
+
+/// Converts a pinned future into a pinned trait object, for building the array passed to `StackJoin::new` by hand.
+pub fn erase<'a, F: Future + 'a>(fut: Pin<&'a mut F>) -> Pin<&'a mut (dyn Future<Output = F::Output> + 'a)> {
+    fut
+}
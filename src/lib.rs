@@ -18,53 +18,314 @@ fn main() {
 }
 ```
 */
-#![cfg_attr(feature = "nightly", feature(unsize, coerce_unsized))]
+#![cfg_attr(feature = "nightly", feature(unsize, coerce_unsized, ptr_metadata))]
 
 extern crate stable_deref_trait;
+#[cfg(feature = "arbitrary")]
+#[doc(hidden)]
+pub extern crate arbitrary;
 
+pub mod any;
+pub mod boxed;
+#[cfg(feature = "executor")]
+pub mod executor;
+mod coerce;
+pub mod guard;
 mod impls;
 pub mod iter;
+pub mod join;
+mod slice;
+pub mod string;
+pub mod thread;
+mod validate;
+pub mod vec;
 
+use std::alloc::Layout;
+use std::cell::UnsafeCell;
+use std::cmp::Ordering;
 use std::marker::PhantomData;
+use std::mem::{ManuallyDrop, MaybeUninit};
+use std::pin::Pin;
+use std::ptr::NonNull;
 use std::{ptr, mem};
 
 /// An owned pointer type to stack-allocated data. See the module-level documentation for further details.
+///
+/// Dropping a `StackPtr` drops the pointee in place, so an array or other collection of `StackPtr`s drops its pointees in the same order as it would drop the values themselves (front to back for arrays). If one pointee panics while being dropped, the remaining elements are still dropped during unwinding, just as with plain values.
+///
+/// For the drop check, the pointee is treated as borrowed for `'a` and `T: 'a` is required, so any data that `T` borrows must be declared before the storage the `StackPtr` points into, and outlive the `StackPtr` itself. Marking `T` as `#[may_dangle]` in the `Drop` impl, as `Box<T>` does, would not relax this: the destructor writes through the `'a` borrow, so `'a` has to be live when it runs, and `T: 'a` then keeps everything `T` borrows alive too. Marking `'a` itself as `#[may_dangle]` would be unsound, since it would let the storage go out of scope before the destructor drops the value in it.
 pub struct StackPtr<'a, T: 'a + ?Sized> {
-    ptr: &'a mut T,
+    // Always points to a valid, initialized `T` that lives for `'a` and that this `StackPtr` owns exclusively.
+    ptr: NonNull<T>,
+    _lifetime: PhantomData<&'a mut T>,
     _marker: PhantomData<T>,
 }
 
+impl<'a, T: 'a> StackPtr<'a, T> {
+    /// Moves `value` into the caller-provided `slot` and returns a `StackPtr` that owns it. This is the safe way to construct a `StackPtr` from a sized value outside of `declare_stackptr!`: the slot determines the lifetime of the `StackPtr`, and since a `MaybeUninit` is never dropped or read implicitly, the value can't be double-dropped. The result can be coerced to an unsized type afterwards.
+    pub fn from_value(slot: &'a mut MaybeUninit<T>, value: T) -> StackPtr<'a, T> {
+        unsafe {
+            StackPtr::from_mut(slot.write(value))
+        }
+    }
+
+    /// Reinterprets a `StackPtr<T>` as a `StackPtr<ManuallyDrop<T>>`, so that dropping it no longer runs `T`'s destructor. `ManuallyDrop<T>` is `#[repr(transparent)]`, so it has the same size, alignment and bit validity as `T` and the pointer can be reused as is. Use `ManuallyDrop::drop` or `from_manually_drop` to control when, or whether, the value is dropped.
+    pub fn into_manually_drop(sp: StackPtr<'a, T>) -> StackPtr<'a, ManuallyDrop<T>> {
+        unsafe {
+            let ptr = StackPtr::into_mut(sp) as *mut T as *mut ManuallyDrop<T>;
+            StackPtr::from_mut(&mut *ptr)
+        }
+    }
+
+    /// Reinterprets a `StackPtr<T>` as a `StackPtr<UnsafeCell<T>>`, so that the value can be mutated through a shared `&StackPtr` under the caller's own synchronization rules. `UnsafeCell<T>` has the same in-memory representation as `T`, so the pointer can be reused as is, and dropping it still drops the `T`.
+    pub fn into_cell(sp: StackPtr<'a, T>) -> StackPtr<'a, UnsafeCell<T>> {
+        unsafe {
+            let ptr = StackPtr::into_mut(sp) as *mut T as *mut UnsafeCell<T>;
+            StackPtr::from_mut(&mut *ptr)
+        }
+    }
+
+    /// Reverses `into_cell`. This is safe because owning the `StackPtr` means there are no other references into the cell.
+    pub fn from_cell(sp: StackPtr<'a, UnsafeCell<T>>) -> StackPtr<'a, T> {
+        unsafe {
+            StackPtr::from_mut(StackPtr::into_mut(sp).get_mut())
+        }
+    }
+
+    /// Converts a `StackPtr<T>` into a one-element `StackPtr<[T]>` over the same storage, for passing a single value to code that consumes slices. This is the inverse of `into_single`; dropping the slice drops the value once.
+    pub fn into_slice_of_one(sp: StackPtr<'a, T>) -> StackPtr<'a, [T]> {
+        unsafe {
+            StackPtr::from_mut(std::slice::from_mut(StackPtr::into_mut(sp)))
+        }
+    }
+
+    /// Reinterprets the storage of a plain-old-data value as an array of its bytes, for serializing stack values without copying. `S` must be `size_of::<T>()`, which is checked in debug builds. The returned `StackPtr` drops the storage as `[u8; S]`, so `T`'s destructor doesn't run; this is meant for `Copy` types, which don't have one. See `from_byte_slice` for the reverse direction.
+    ///
+    /// # Safety
+    ///
+    /// `S` must equal `size_of::<T>()`. Every byte of the value must be initialized, so `T` must not contain padding, as can happen in a `#[repr(C)]` struct whose fields don't line up, or uninitialized parts like a `MaybeUninit` field. `T` must also not rely on its bytes staying unchanged, since they can be modified through the byte array.
+    pub unsafe fn as_bytes_array<const S: usize>(sp: StackPtr<'a, T>) -> StackPtr<'a, [u8; S]> {
+        debug_assert_eq!(S, mem::size_of::<T>(), "byte array length does not match the size of the source type");
+        let ptr = StackPtr::into_mut(sp) as *mut T as *mut [u8; S];
+        StackPtr::from_mut(&mut *ptr)
+    }
+
+    /// Adopts a value that the caller has wrapped in `ManuallyDrop`, returning a `StackPtr` that will run `T`'s destructor. The `ManuallyDrop` wrapper makes sure the original binding never drops the value a second time.
+    ///
+    /// # Safety
+    ///
+    /// This can't be a safe function: once the `StackPtr` is dropped, the `ManuallyDrop` binding is still there and still readable, and nothing stops the same binding from being adopted twice. The caller must make sure the value is adopted at most once, and that it isn't used through the binding after the `StackPtr` has been dropped. The value must not have been dropped or taken out with `ManuallyDrop::drop` or `ManuallyDrop::take` either.
+    pub unsafe fn from_manually_drop_ref(md: &'a mut ManuallyDrop<T>) -> StackPtr<'a, T> {
+        StackPtr::from_mut(&mut **md)
+    }
+
+    /// Reverses `into_manually_drop`, so that dropping the `StackPtr` runs `T`'s destructor again.
+    ///
+    /// # Safety
+    ///
+    /// The value must not have been dropped or taken out already, with `ManuallyDrop::drop` or `ManuallyDrop::take`.
+    pub unsafe fn from_manually_drop(sp: StackPtr<'a, ManuallyDrop<T>>) -> StackPtr<'a, T> {
+        let ptr = StackPtr::into_mut(sp) as *mut ManuallyDrop<T> as *mut T;
+        StackPtr::from_mut(&mut *ptr)
+    }
+}
+
 impl<'a, T: 'a + ?Sized> StackPtr<'a, T> {
-    /// Constructs a new `StackPtr` from an `&mut` reference. The `StackPtr` will assume ownership of the pointed-to value, so make sure you call `std::mem::forget` on the value to avoid double-drop. The `declare_stackptr!` macro does this all safely for you.
+    /// Constructs a new `StackPtr` from an `&mut` reference. This is the low-level path for advanced use, e.g. adopting an already-unsized value; prefer `from_value` or the `declare_stackptr!` macro, which do this safely. The `StackPtr` will assume ownership of the pointed-to value, so make sure you call `std::mem::forget` on the value to avoid double-drop.
+    ///
+    /// # Safety
+    ///
+    /// The caller must treat the pointed-to value as moved into the `StackPtr`: it must not be used or dropped again once the `StackPtr` has been created.
     pub unsafe fn from_mut(ptr: &'a mut T) -> StackPtr<'a, T> {
         StackPtr {
-            ptr: ptr,
+            ptr: NonNull::from(ptr),
+            _lifetime: PhantomData,
             _marker: PhantomData,
         }
     }
 
+    /// Adopts a pinned value, e.g. one pinned on the stack with `std::pin::pin!`, as a `StackPtr`. The `StackPtr` never moves its pointee, so the value stays where it was pinned.
+    ///
+    /// # Safety
+    ///
+    /// The `StackPtr` takes over dropping the value, so the place it was pinned in must not drop it again; with `pin!` this means pinning a `ManuallyDrop` and projecting through it. If `T` is not `Unpin`, the caller must also uphold the pinning drop guarantee: the `StackPtr` has to be dropped, rather than forgotten, before the storage goes out of scope.
+    pub unsafe fn from_pinned(pin: Pin<&'a mut T>) -> StackPtr<'a, T> {
+        StackPtr::from_mut(Pin::get_unchecked_mut(pin))
+    }
+
+    /// Pins the pointee in place, for pointees that aren't `Unpin`. For `Unpin` types, use the safe `From` impl instead, as in `Pin::from(sp)` or `sp.into()`.
+    ///
+    /// # Safety
+    ///
+    /// The storage is stable for as long as the `StackPtr` exists, but it is reclaimed when its stack frame returns whether or not the value was dropped. The caller must therefore make sure the returned `Pin` is dropped, not forgotten or leaked, before the storage goes out of scope, so that the pinning drop guarantee holds. `pin_stackptr!` takes care of this for you.
+    pub unsafe fn into_pin_unchecked(sp: StackPtr<'a, T>) -> Pin<StackPtr<'a, T>> {
+        Pin::new_unchecked(sp)
+    }
+
+    /// Extends the lifetime of a `StackPtr` to `'static`, for storing it in a `'static` context. This is the `StackPtr` equivalent of transmuting a reference's lifetime.
+    ///
+    /// # Safety
+    ///
+    /// The storage the `StackPtr` points into must actually live for the rest of the program, for example because it was leaked with `Box::leak` or is a `static`, and must not be used through any other path while the returned `StackPtr` exists. A `StackPtr` into an ordinary stack frame must never be passed here.
+    pub unsafe fn assume_static(sp: StackPtr<'a, T>) -> StackPtr<'static, T> where T: 'static {
+        let ptr = StackPtr::into_mut(sp) as *mut T;
+        StackPtr::from_mut(&mut *ptr)
+    }
+
+    /// Changes the lifetime of a `StackPtr` to an arbitrary `'b`, for storing it somewhere with a different lifetime regime than the one the compiler inferred. Shortening the lifetime never needs this, since `StackPtr` is covariant in `'a`; this is for the cases where the compiler can't see that the storage lives long enough. Prefer it over `mem::transmute`, which would also let the pointee type change by accident. See `assume_static` for the `'static` case.
+    ///
+    /// # Safety
+    ///
+    /// Nothing about this is checked. The caller must guarantee that the storage the `StackPtr` points into stays valid, and isn't used through any other path, for all of the time the returned `StackPtr` is actually used, and that it is dropped or forgotten before the storage goes away. In practice this means the `StackPtr` must be done with before the stack frame that owns the storage returns, even though `'b` itself may be longer. For example, `thread::scope` requires spawned closures to outlive the whole scope, which storage declared inside the scope's closure doesn't, but joining the thread before that closure returns is enough:
+    ///
+    /// ```
+    /// # #[macro_use] extern crate stack_ptr;
+    /// # use stack_ptr::StackPtr;
+    /// # fn main() {
+    /// std::thread::scope(|scope| {
+    ///     declare_stackptr!{
+    ///         let numbers: StackPtr<Vec<i32>> = StackPtr::new(vec![1, 2, 3]);
+    ///     }
+    ///
+    ///     // `spawn` needs `numbers` to live as long as the scope, but the thread is joined before this closure returns
+    ///     let numbers = unsafe { StackPtr::change_lifetime(numbers) };
+    ///     let handle = scope.spawn(move || numbers.iter().sum::<i32>());
+    ///     assert_eq!(handle.join().unwrap(), 6);
+    /// });
+    /// # }
+    /// ```
+    pub unsafe fn change_lifetime<'b>(sp: StackPtr<'a, T>) -> StackPtr<'b, T> where T: 'b {
+        let ptr = StackPtr::into_mut(sp) as *mut T;
+        StackPtr::from_mut(&mut *ptr)
+    }
+
+    /// Narrows a `StackPtr` down to part of its pointee, such as a field, by calling `f` to borrow that part and rebuilding a `StackPtr` around the borrow. The parent is forgotten rather than dropped: the returned `StackPtr` drops the part it points to and nothing else.
+    ///
+    /// # Safety
+    ///
+    /// Everything in the parent outside the projected part is leaked, so the rest of `T` must not need dropping, or the caller must drop it by hand inside `f` (for instance with `ptr::drop_in_place` on the other fields) without touching the projected part. The part `f` returns must not be dropped by anything else either: this rules out projecting through a `ManuallyDrop` the caller will later drop, or into data `T` doesn't own inline, like the contents of a `Vec`. Projecting a newtype down to the value it wraps, or a struct down to one field when the others are `Copy`, is always fine.
+    pub unsafe fn project<U: ?Sized, F: FnOnce(&mut T) -> &mut U>(sp: StackPtr<'a, T>, f: F) -> StackPtr<'a, U> {
+        let part = f(StackPtr::into_mut(sp)) as *mut U;
+        StackPtr::from_mut(&mut *part)
+    }
+
+    /// Returns the size and alignment of the pointee. For slices and trait objects these are read from the pointer metadata, as with `Layout::for_value`.
+    pub fn layout(sp: &StackPtr<'a, T>) -> Layout {
+        Layout::for_value(&**sp)
+    }
+
+    /// Compares the pointees of two `StackPtr`s with a custom comparator, for generic sorting and selection code that takes one. `StackPtr` doesn't implement `PartialOrd` or `Ord` itself; to compare with the pointee's own ordering, dereference both sides, as in `*a < *b` or `(*a).cmp(&*b)`.
+    pub fn cmp_by<F: FnMut(&T, &T) -> Ordering>(a: &StackPtr<'a, T>, b: &StackPtr<'a, T>, mut f: F) -> Ordering {
+        f(&**a, &**b)
+    }
+
+    /// Consumes a `StackPtr` without running the pointee's destructor, the `StackPtr` counterpart of `mem::forget`.
+    ///
+    /// Leaking a `StackPtr` never leaks memory: the storage belongs to a stack frame and is reclaimed when that frame returns, as usual. What is skipped is `T`'s destructor, so any resources the value owns (heap allocations, file handles, locks) are leaked, and code that relies on the destructor running, such as a guard, will not see it. This is safe, just like `mem::forget`, but it does mean a pointee obtained through one of the unsafe constructors is left in its initialized state, and the original owner must not assume it was dropped.
+    pub fn forget(sp: StackPtr<'a, T>) {
+        mem::forget(sp)
+    }
+
+    /// Takes the `StackPtr` out of `slot`, leaving `None` in its place. This is just `Option::take`, spelled out for code that threads an `Option<StackPtr>` through; an `Option<StackPtr<T>>` is the same size as a `StackPtr<T>`, since `None` is represented by a null pointer, so storing `None` costs nothing.
+    pub fn take_option(slot: &mut Option<StackPtr<'a, T>>) -> Option<StackPtr<'a, T>> {
+        slot.take()
+    }
+
     /// Consumes a `StackPtr` without dropping it, and returns a `&mut` reference to the data. Useful for doing a coercion on the reference and reconstructing a new `StackPtr` with `from_mut`.
     pub fn into_mut(sp: StackPtr<'a, T>) -> &'a mut T {
         unsafe {
-            let ptr = sp.ptr as *mut T;
+            let ptr = sp.ptr.as_ptr();
             mem::forget(sp);
             &mut *ptr
         }
     }
+
+    /// Consumes a `StackPtr` without dropping it, returning a raw pointer to the data along with a marker carrying the `StackPtr`'s lifetime. Passing both to `from_raw_parts`, possibly after casting the pointer, reconstructs a `StackPtr` with the same lifetime.
+    pub fn into_raw_parts(sp: StackPtr<'a, T>) -> (*mut T, PhantomData<&'a mut ()>) {
+        (StackPtr::into_non_null(sp).as_ptr(), PhantomData)
+    }
+
+    /// Returns a `NonNull` pointer to the data, for passing to APIs that work with `NonNull`. The `StackPtr` keeps ownership. Since the pointer is derived from a shared borrow, it may only be used for reads, and only while that borrow lasts.
+    pub fn as_non_null(sp: &StackPtr<'a, T>) -> NonNull<T> {
+        NonNull::from(&**sp)
+    }
+
+    /// Consumes a `StackPtr` without dropping it, returning a `NonNull` pointer to the data. The caller becomes responsible for dropping the value, for example with `ptr::drop_in_place` or by passing the pointer back to `from_raw_parts`.
+    pub fn into_non_null(sp: StackPtr<'a, T>) -> NonNull<T> {
+        let ptr = sp.ptr;
+        mem::forget(sp);
+        ptr
+    }
+
+    /// Reconstructs a `StackPtr` from the raw parts returned by `into_raw_parts`.
+    ///
+    /// # Safety
+    ///
+    /// `ptr` must point to a valid, initialized `T` that stays alive for `'a` and that nothing else will use or drop, since the `StackPtr` takes over ownership of it.
+    pub unsafe fn from_raw_parts(ptr: *mut T, _lifetime: PhantomData<&'a mut ()>) -> StackPtr<'a, T> {
+        StackPtr::from_mut(&mut *ptr)
+    }
 }
 
+#[cfg(feature = "nightly")]
+impl<'a, T: 'a + ?Sized> StackPtr<'a, T> {
+    /// Returns the pointer metadata of the pointee: its length for slices, its vtable for trait objects, and `()` for sized types.
+    pub fn metadata(sp: &StackPtr<'a, T>) -> <T as ptr::Pointee>::Metadata {
+        ptr::metadata(&**sp as *const T)
+    }
+
+    /// Reconstructs a `StackPtr` from a data pointer and pointer metadata, as obtained from `metadata` and `into_raw_parts`.
+    ///
+    /// # Safety
+    ///
+    /// `data` and `meta` must together describe a valid, initialized `T` that stays alive for `'a` and that nothing else will use or drop, since the `StackPtr` takes over ownership of it.
+    pub unsafe fn from_raw_parts_with_meta(data: *mut (), meta: <T as ptr::Pointee>::Metadata) -> StackPtr<'a, T> {
+        StackPtr::from_mut(&mut *ptr::from_raw_parts_mut(data, meta))
+    }
+}
+
+/// With the `debug-poison` feature enabled, debug builds overwrite the pointee's bytes with `0xA5` once it has been dropped. This is a best-effort diagnostic for the raw-pointer paths (`into_raw_parts`, `into_non_null` and friends): a stale pointer that is used after the value was dropped then reads an obvious pattern instead of plausible old data. It compiles out in release builds.
 impl<'a, T: ?Sized> Drop for StackPtr<'a, T> {
     fn drop(&mut self) {
+        #[cfg(all(feature = "debug-poison", debug_assertions))]
+        let size = mem::size_of_val(&**self);
         unsafe {
-            ptr::drop_in_place(self.ptr);
+            ptr::drop_in_place(self.ptr.as_ptr());
+            #[cfg(all(feature = "debug-poison", debug_assertions))]
+            ptr::write_bytes(self.ptr.as_ptr() as *mut u8, POISON, size);
         }
     }
 }
 
-#[doc(hidden)]
-#[inline(always)]
-pub unsafe fn construct_mut_ref<'a, T: ?Sized>(ptr: *mut T, _lifetime_marker: &'a mut ()) -> &'a mut T {
-    &mut *ptr
+#[cfg(all(feature = "debug-poison", debug_assertions))]
+const POISON: u8 = 0xA5;
+
+/// Extension trait for moving any value onto the stack and working with it through a `StackPtr`, as a method-chaining alternative to `declare_stackptr!`.
+///
+/// ```
+/// use stack_ptr::StackAllocate;
+///
+/// let len = vec![1, 2, 3].on_stack(|sp| sp.len());
+/// assert_eq!(len, 3);
+/// ```
+///
+/// The `StackPtr` only lives as long as the closure call, so it can't be returned from it:
+///
+/// ```compile_fail
+/// use stack_ptr::StackAllocate;
+///
+/// let escaped = 5.on_stack(|sp| sp);
+/// ```
+pub trait StackAllocate: Sized {
+    /// Moves `self` onto the stack and passes ownership of it to `f` as a `StackPtr`. The value is dropped when the `StackPtr` is, unless `f` consumes it first.
+    fn on_stack<U, F: for<'a> FnOnce(StackPtr<'a, Self>) -> U>(self, f: F) -> U;
+}
+
+impl<T> StackAllocate for T {
+    fn on_stack<U, F: for<'a> FnOnce(StackPtr<'a, T>) -> U>(self, f: F) -> U {
+        let mut value = ManuallyDrop::new(self);
+        f(unsafe { StackPtr::from_mut(&mut *value) })
+    }
 }
 
 #[doc(hidden)]
@@ -82,14 +343,18 @@ macro_rules! __declare_stackptr_variable {
 #[macro_export]
 macro_rules! __declare_stackptr {
     ($mutable:ident, $name:ident, $ty:ty, $expr:expr) => {
-        let mut _value = $expr;
-        let mut _lifetime_marker = ();
+        let mut _slot = ::std::mem::MaybeUninit::uninit();
+        let _value = $crate::StackPtr::from_value(&mut _slot, $expr);
         __declare_stackptr_variable!($mutable, $name, unsafe {
-            let ptr = &mut _value as *mut $ty;
-            ::std::mem::forget(_value);
-            StackPtr::from_mut($crate::construct_mut_ref(ptr, &mut _lifetime_marker))
+            let _ref = $crate::StackPtr::into_mut(_value);
+            let _ref: &mut $ty = _ref;
+            $crate::StackPtr::from_mut(_ref)
         });
     };
+    ($mutable:ident, $name:ident, $expr:expr) => {
+        let mut _slot = ::std::mem::MaybeUninit::uninit();
+        __declare_stackptr_variable!($mutable, $name, $crate::StackPtr::from_value(&mut _slot, $expr));
+    };
 }
 
 /// Safely declare a `StackPtr` with the appropriate lifetime at this point on the stack.
@@ -99,23 +364,553 @@ macro_rules! declare_stackptr {
         __declare_stackptr!(no, $name, $ty, $expr)
     };
     (let $name:ident = StackPtr::new($expr:expr);) => {
-        __declare_stackptr!(no, $name, _, $expr)
+        __declare_stackptr!(no, $name, $expr)
     };
     (let mut $name:ident: StackPtr<$ty:ty> = StackPtr::new($expr:expr);) => {
         __declare_stackptr!(yes, $name, $ty, $expr)
     };
     (let mut $name:ident = StackPtr::new($expr:expr);) => {
-        __declare_stackptr!(yes, $name, _, $expr)
+        __declare_stackptr!(yes, $name, $expr)
+    };
+}
+
+/// Like `declare_stackptr!`, but for an initializer that returns a `Result`. On `Ok`, the value is moved into storage at this point on the stack and bound as a `StackPtr`; on `Err`, the error is returned from the enclosing function with `?`, so it goes through `From` like any other `?`. The storage is left uninitialized on the error path, so there is nothing to drop or roll back.
+///
+/// ```
+/// # #[macro_use] extern crate stack_ptr;
+/// # use std::num::ParseIntError;
+/// fn parse_sum(a: &str, b: &str) -> Result<i32, ParseIntError> {
+///     try_declare_stackptr!{
+///         let x = StackPtr::try_new(a.parse::<i32>());
+///     }
+///     try_declare_stackptr!{
+///         let y = StackPtr::try_new(b.parse::<i32>());
+///     }
+///     Ok(*x + *y)
+/// }
+///
+/// # fn main() {
+/// assert_eq!(parse_sum("1", "2"), Ok(3));
+/// assert!(parse_sum("1", "two").is_err());
+/// # }
+/// ```
+#[macro_export]
+macro_rules! try_declare_stackptr {
+    (let $name:ident: StackPtr<$ty:ty> = StackPtr::try_new($expr:expr);) => {
+        __declare_stackptr!(no, $name, $ty, $expr?)
+    };
+    (let $name:ident = StackPtr::try_new($expr:expr);) => {
+        __declare_stackptr!(no, $name, $expr?)
+    };
+    (let mut $name:ident: StackPtr<$ty:ty> = StackPtr::try_new($expr:expr);) => {
+        __declare_stackptr!(yes, $name, $ty, $expr?)
+    };
+    (let mut $name:ident = StackPtr::try_new($expr:expr);) => {
+        __declare_stackptr!(yes, $name, $expr?)
+    };
+}
+
+/// Declares several `StackPtr`s at once, accepting any number of the `let` statements that `declare_stackptr!` accepts. Each binding is expanded separately and gets its own backing storage.
+///
+/// ```
+/// # #[macro_use] extern crate stack_ptr;
+/// # use std::fmt::Debug;
+/// # fn main() {
+/// declare_stackptrs! {
+///     let a = StackPtr::new(1);
+///     let b: StackPtr<dyn Debug> = StackPtr::new("two");
+///     let mut c: StackPtr<[i32]> = StackPtr::new([3, 4]);
+/// }
+/// c[0] += *a;
+/// assert_eq!(format!("{:?} {:?}", b, &*c), "\"two\" [4, 4]");
+/// # }
+/// ```
+#[macro_export]
+macro_rules! declare_stackptrs {
+    () => {};
+    (let $name:ident: StackPtr<$ty:ty> = StackPtr::new($expr:expr); $($rest:tt)*) => {
+        __declare_stackptr!(no, $name, $ty, $expr);
+        declare_stackptrs!($($rest)*);
+    };
+    (let $name:ident = StackPtr::new($expr:expr); $($rest:tt)*) => {
+        __declare_stackptr!(no, $name, $expr);
+        declare_stackptrs!($($rest)*);
+    };
+    (let mut $name:ident: StackPtr<$ty:ty> = StackPtr::new($expr:expr); $($rest:tt)*) => {
+        __declare_stackptr!(yes, $name, $ty, $expr);
+        declare_stackptrs!($($rest)*);
+    };
+    (let mut $name:ident = StackPtr::new($expr:expr); $($rest:tt)*) => {
+        __declare_stackptr!(yes, $name, $expr);
+        declare_stackptrs!($($rest)*);
+    };
+}
+
+/// Declares a value on the stack and pins it in place, the heap-free counterpart of `Box::pin`. The binding has type `Pin<&mut T>` (or `Pin<&mut U>` with a type annotation, as in `declare_stackptr!`), and the `StackPtr` that owns the value is kept in a hidden local, so no unpinned access to the value is ever available.
+///
+/// The macro deliberately doesn't hand out a `Pin<StackPtr<T>>`: that could be passed to `mem::forget`, after which the stack frame would be reclaimed without running the value's destructor, breaking the drop guarantee that `Pin` promises. The hidden `StackPtr` always drops the value before its storage goes out of scope, even when unwinding.
+///
+/// ```
+/// # #[macro_use] extern crate stack_ptr;
+/// # use std::future::{self, Future};
+/// # use std::task::{Context, Poll, Waker};
+/// # fn main() {
+/// pin_stackptr! {
+///     let fut: StackPtr<dyn Future<Output = i32>> = StackPtr::new(future::ready(1));
+/// }
+/// let mut cx = Context::from_waker(Waker::noop());
+/// assert_eq!(fut.as_mut().poll(&mut cx), Poll::Ready(1));
+/// # }
+/// ```
+#[macro_export]
+macro_rules! pin_stackptr {
+    (let $name:ident: StackPtr<$ty:ty> = StackPtr::new($expr:expr);) => {
+        declare_stackptr!(let mut _pinned: StackPtr<$ty> = StackPtr::new($expr););
+        #[allow(unused_mut)]
+        let mut $name = unsafe { ::std::pin::Pin::new_unchecked(&mut *_pinned) };
+    };
+    (let $name:ident = StackPtr::new($expr:expr);) => {
+        declare_stackptr!(let mut _pinned = StackPtr::new($expr););
+        #[allow(unused_mut)]
+        let mut $name = unsafe { ::std::pin::Pin::new_unchecked(&mut *_pinned) };
+    };
+}
+
+/// Declares several futures on the stack, pins each of them in place as `pin_stackptr!` does, and binds a `join::StackJoin` that completes once all of them have, with their outputs in order as an array. All the futures must have the same output type. Like `pin_stackptr!`, the futures are owned by hidden locals, so they are always dropped in place.
+///
+/// ```
+/// # #[macro_use] extern crate stack_ptr;
+/// # use std::future::{self, Future};
+/// # use std::task::{Context, Poll, Waker};
+/// # fn main() {
+/// join_stack!(let mut joined = future::ready(1), async_double(2), future::ready(3));
+/// let mut cx = Context::from_waker(Waker::noop());
+/// assert_eq!(std::pin::Pin::new(&mut joined).poll(&mut cx), Poll::Ready([1, 4, 3]));
+/// # }
+/// # fn async_double(x: i32) -> impl Future<Output = i32> { future::ready(x * 2) }
+/// ```
+#[macro_export]
+macro_rules! join_stack {
+    (@pin [$($pinned:ident)*] ($($binding:tt)+) ) => {
+        let $($binding)+ = $crate::join::StackJoin::new([$($crate::join::erase($pinned)),*]);
+    };
+    (@pin [$($pinned:ident)*] ($($binding:tt)+) $fut:expr $(, $rest:expr)*) => {
+        pin_stackptr!{
+            let _fut = StackPtr::new($fut);
+        }
+        join_stack!(@pin [$($pinned)* _fut] ($($binding)+) $($rest),*);
+    };
+    (let $name:ident = $($fut:expr),+ $(,)*) => {
+        join_stack!(@pin [] ($name) $($fut),+);
+    };
+    (let mut $name:ident = $($fut:expr),+ $(,)*) => {
+        join_stack!(@pin [] (mut $name) $($fut),+);
     };
 }
 
 /// An implementation of `std::ops::CoerceUnsized` on stable rust. On nightly, you can convert a `StackPtr<T>` into a `StackPtr<U>` if `T` implements `U`, with `let sp = sp as StackPtr<U>;`, but this requires the unstable `CoerceUnsized` trait. On stable you can do `let sp = coerce_stackptr!(sp, U);`.
+///
+/// The conversion goes through an ordinary `&mut` reference coercion, so only genuine unsizing coercions (arrays to slices, concrete types to trait objects they implement) are accepted. Narrowing a trait object is one of these too: auto traits can be dropped, as in `coerce_stackptr!(sp, dyn Debug)` for a `StackPtr<dyn Debug + Send>`, and a trait object can be upcast to one of its supertraits.
+///
+/// ```
+/// # #[macro_use] extern crate stack_ptr;
+/// # use std::error::Error;
+/// # use std::fmt::Debug;
+/// # fn main() {
+/// declare_stackptr!{
+///     let err: StackPtr<dyn Error + Send + Sync> = StackPtr::new(std::fmt::Error);
+/// }
+/// let err = coerce_stackptr!(err, dyn Error);
+/// let err = coerce_stackptr!(err, dyn Debug);
+/// assert_eq!(format!("{:?}", err), "Error");
+/// # }
+/// ```
 #[macro_export]
 macro_rules! coerce_stackptr {
     ($sp:expr, $ty:ty) => {{
-        let (ptr, lifetime) = $crate::StackPtr::into_raw_parts($sp);
+        let _ref = $crate::StackPtr::into_mut($sp);
+        let _ref: &mut $ty = _ref;
         unsafe {
-            $crate::StackPtr::from_raw_parts(ptr as *mut $ty, lifetime)
+            $crate::StackPtr::from_mut(_ref)
         }
     }};
 }
+
+/// Converts a `StackPtr<T>` into a `StackPtr<dyn Trait>`, e.g. `as_dyn!(sp, Debug)` or `as_dyn!(sp, Iterator<Item = u8> + Send)`. Like `coerce_stackptr!`, the fat pointer is produced by the compiler's own reference unsizing, so the vtable always matches the concrete type.
+///
+/// ```
+/// # #[macro_use] extern crate stack_ptr;
+/// # use stack_ptr::StackPtr;
+/// # use std::fmt::Debug;
+/// # fn main() {
+/// declare_stackptr! {
+///     let value = StackPtr::new(vec![1, 2]);
+/// }
+/// let value: StackPtr<dyn Debug> = as_dyn!(value, Debug);
+/// assert_eq!(format!("{:?}", value), "[1, 2]");
+/// # }
+/// ```
+#[macro_export]
+macro_rules! as_dyn {
+    ($sp:expr, $($bounds:tt)+) => {
+        coerce_stackptr!($sp, dyn $($bounds)+)
+    };
+}
+
+/// Concatenates two `StackPtr<[T]>`s into a new `StackPtr<[T]>` backed by a buffer of the given capacity, declared at this point on the stack. The elements are moved rather than cloned, and both inputs are consumed. Panics if the combined length exceeds the capacity.
+///
+/// ```
+/// # #[macro_use] extern crate stack_ptr;
+/// # use stack_ptr::StackPtr;
+/// # fn main() {
+/// declare_stackptr! {
+///     let a: StackPtr<[i32]> = StackPtr::new([1, 2]);
+/// }
+/// declare_stackptr! {
+///     let b: StackPtr<[i32]> = StackPtr::new([3]);
+/// }
+/// stack_concat!(let joined = 4, a, b);
+/// assert_eq!(&*joined, &[1, 2, 3]);
+/// # }
+/// ```
+#[macro_export]
+macro_rules! stack_concat {
+    (let $name:ident = $capacity:expr, $a:expr, $b:expr) => {
+        let mut _buf: [::std::mem::MaybeUninit<_>; $capacity] = [const { ::std::mem::MaybeUninit::uninit() }; $capacity];
+        let $name = $crate::StackPtr::concat_into(&mut _buf, $a, $b);
+    };
+    (let mut $name:ident = $capacity:expr, $a:expr, $b:expr) => {
+        let mut _buf: [::std::mem::MaybeUninit<_>; $capacity] = [const { ::std::mem::MaybeUninit::uninit() }; $capacity];
+        let mut $name = $crate::StackPtr::concat_into(&mut _buf, $a, $b);
+    };
+}
+
+/// Declares an array of the given elements on the stack and binds it as a `StackPtr<[T]>`, inferring `T` and the length. The array lives in a hidden local in the caller's scope, so the binding can be used for the rest of that scope.
+///
+/// ```
+/// # #[macro_use] extern crate stack_ptr;
+/// # fn main() {
+/// stack_slice!(let names = String::from("a"), String::from("b"));
+/// assert_eq!(names.len(), 2);
+/// let joined: String = names.into_iter().collect();
+/// assert_eq!(joined, "ab");
+/// # }
+/// ```
+#[macro_export]
+macro_rules! stack_slice {
+    (let $name:ident = $($elem:expr),+ $(,)?) => {
+        let mut _slot = ::std::mem::MaybeUninit::uninit();
+        let $name = coerce_stackptr!($crate::StackPtr::from_value(&mut _slot, [$($elem),+]), [_]);
+    };
+    (let mut $name:ident = $($elem:expr),+ $(,)?) => {
+        let mut _slot = ::std::mem::MaybeUninit::uninit();
+        let mut $name = coerce_stackptr!($crate::StackPtr::from_value(&mut _slot, [$($elem),+]), [_]);
+    };
+}
+
+/// Creates a `StackPtr` that owns a single field of a larger value, e.g. `unsafe { stack_field!(&mut parent.field) }`. The `StackPtr` borrows the parent for its lifetime and drops just that field.
+///
+/// The expansion calls `StackPtr::from_mut`, so it has to be used inside an `unsafe` block.
+///
+/// # Safety
+///
+/// The field ends up with two owners: the `StackPtr`, and the parent value whose drop glue would drop it too. The parent must therefore never drop the field, which usually means it is wrapped in `ManuallyDrop` and its other fields are dropped or moved out by hand. The field must also not be read or moved out of through the parent after the `StackPtr` has been dropped.
+#[macro_export]
+macro_rules! stack_field {
+    (&mut $place:expr) => {
+        $crate::StackPtr::from_mut(&mut $place)
+    };
+}
+
+/// Collects exactly `N` items from an iterator into an array on the stack, binding it as a `StackPtr<[T; N]>`. Use `coerce_stackptr!` to turn the result into a `StackPtr<[T]>` if needed.
+///
+/// # Panics
+///
+/// Panics if the iterator yields fewer or more than `N` items. See `StackPtr::collect_exact`.
+///
+/// ```
+/// # #[macro_use] extern crate stack_ptr;
+/// # fn main() {
+/// stack_collect_exact!(let squares = 4, (1..=4).map(|x| x * x));
+/// assert_eq!(*squares, [1, 4, 9, 16]);
+/// # }
+/// ```
+#[macro_export]
+macro_rules! stack_collect_exact {
+    (let $name:ident = $n:expr, $iter:expr) => {
+        let mut _slot: ::std::mem::MaybeUninit<[_; $n]> = ::std::mem::MaybeUninit::uninit();
+        let $name = $crate::StackPtr::collect_exact(&mut _slot, $iter);
+    };
+    (let mut $name:ident = $n:expr, $iter:expr) => {
+        let mut _slot: ::std::mem::MaybeUninit<[_; $n]> = ::std::mem::MaybeUninit::uninit();
+        let mut $name = $crate::StackPtr::collect_exact(&mut _slot, $iter);
+    };
+}
+
+/// Moves the elements of two equal-length `StackPtr<[T]>`s alternately into a new stack buffer with room for `capacity` elements, binding the result as a `StackPtr<[T]>`. See `StackPtr::interleave_into`.
+///
+/// # Panics
+///
+/// Panics if the inputs have different lengths or hold more than `capacity` elements in total.
+///
+/// ```
+/// # #[macro_use] extern crate stack_ptr;
+/// # fn main() {
+/// stack_slice!(let a = 1, 3);
+/// stack_slice!(let b = 2, 4);
+/// stack_interleave!(let merged = 4, a, b);
+/// assert_eq!(merged, [1, 2, 3, 4]);
+/// # }
+/// ```
+#[macro_export]
+macro_rules! stack_interleave {
+    (let $name:ident = $capacity:expr, $a:expr, $b:expr) => {
+        let mut _buf: [::std::mem::MaybeUninit<_>; $capacity] = [const { ::std::mem::MaybeUninit::uninit() }; $capacity];
+        let $name = $crate::StackPtr::interleave_into(&mut _buf, $a, $b);
+    };
+    (let mut $name:ident = $capacity:expr, $a:expr, $b:expr) => {
+        let mut _buf: [::std::mem::MaybeUninit<_>; $capacity] = [const { ::std::mem::MaybeUninit::uninit() }; $capacity];
+        let mut $name = $crate::StackPtr::interleave_into(&mut _buf, $a, $b);
+    };
+}
+
+/// Moves the elements of every `StackPtr<[T]>` yielded by an iterator into a new stack buffer with room for `capacity` elements, binding the result as a `StackPtr<[T]>`. See `StackPtr::concat_iter_into`.
+///
+/// # Panics
+///
+/// Panics if the inputs hold more than `capacity` elements in total.
+///
+/// ```
+/// # #[macro_use] extern crate stack_ptr;
+/// # fn main() {
+/// stack_slice!(let a = 1, 2);
+/// stack_slice!(let b = 3);
+/// stack_concat_iter!(let joined = 4, vec![a, b]);
+/// assert_eq!(joined, [1, 2, 3]);
+/// # }
+/// ```
+#[macro_export]
+macro_rules! stack_concat_iter {
+    (let $name:ident = $capacity:expr, $iter:expr) => {
+        let mut _buf: [::std::mem::MaybeUninit<_>; $capacity] = [const { ::std::mem::MaybeUninit::uninit() }; $capacity];
+        let $name = $crate::StackPtr::concat_iter_into(&mut _buf, $iter);
+    };
+    (let mut $name:ident = $capacity:expr, $iter:expr) => {
+        let mut _buf: [::std::mem::MaybeUninit<_>; $capacity] = [const { ::std::mem::MaybeUninit::uninit() }; $capacity];
+        let mut $name = $crate::StackPtr::concat_iter_into(&mut _buf, $iter);
+    };
+}
+
+/// Declares a stack buffer of `N` elements and fills each slot `i` with the result of calling a closure with `i`, binding the result as a `StackPtr<[T]>` of length `N`. This is the stack counterpart of `std::array::from_fn`. See `StackPtr::from_fn_into`.
+///
+/// ```
+/// # #[macro_use] extern crate stack_ptr;
+/// # fn main() {
+/// stack_from_fn!(let squares = 4, |i| i * i);
+/// assert_eq!(squares, [0, 1, 4, 9]);
+/// # }
+/// ```
+#[macro_export]
+macro_rules! stack_from_fn {
+    (let $name:ident = $n:expr, $f:expr) => {
+        let mut _buf: [::std::mem::MaybeUninit<_>; $n] = [const { ::std::mem::MaybeUninit::uninit() }; $n];
+        let $name = $crate::StackPtr::from_fn_into(&mut _buf, $f);
+    };
+    (let mut $name:ident = $n:expr, $f:expr) => {
+        let mut _buf: [::std::mem::MaybeUninit<_>; $n] = [const { ::std::mem::MaybeUninit::uninit() }; $n];
+        let mut $name = $crate::StackPtr::from_fn_into(&mut _buf, $f);
+    };
+}
+
+/// Fills a new stack buffer with room for `capacity` elements with `n` copies of a value, binding the result as a `StackPtr<[T]>` of length `n`. `capacity` must be a constant, but `n` can be decided at runtime. See `StackPtr::repeat_into`.
+///
+/// # Panics
+///
+/// Panics if `n` is greater than `capacity`.
+///
+/// ```
+/// # #[macro_use] extern crate stack_ptr;
+/// # fn main() {
+/// let n = 3;
+/// stack_repeat!(let zeros = 8, 0u8, n);
+/// assert_eq!(zeros, [0, 0, 0]);
+/// # }
+/// ```
+#[macro_export]
+macro_rules! stack_repeat {
+    (let $name:ident = $capacity:expr, $value:expr, $n:expr) => {
+        let mut _buf: [::std::mem::MaybeUninit<_>; $capacity] = [const { ::std::mem::MaybeUninit::uninit() }; $capacity];
+        let $name = $crate::StackPtr::repeat_into(&mut _buf, $value, $n);
+    };
+    (let mut $name:ident = $capacity:expr, $value:expr, $n:expr) => {
+        let mut _buf: [::std::mem::MaybeUninit<_>; $capacity] = [const { ::std::mem::MaybeUninit::uninit() }; $capacity];
+        let mut $name = $crate::StackPtr::repeat_into(&mut _buf, $value, $n);
+    };
+}
+
+/// Declares a slot in the caller's stack frame and passes it to a helper that initializes it, binding the `StackPtr` the helper returns. The helper has the shape `fn build<'a>(slot: &'a mut MaybeUninit<T>, args...) -> StackPtr<'a, T>`, typically ending in `StackPtr::from_value(slot, value)`, and any extra arguments are passed after the slot. Since the caller owns the storage, this is the way to factor the construction of a `StackPtr` out into a function.
+///
+/// ```
+/// # #[macro_use] extern crate stack_ptr;
+/// use std::mem::MaybeUninit;
+/// use stack_ptr::StackPtr;
+///
+/// fn numbers<'a>(slot: &'a mut MaybeUninit<[u32; 3]>, start: u32) -> StackPtr<'a, [u32; 3]> {
+///     StackPtr::from_value(slot, [start, start + 1, start + 2])
+/// }
+///
+/// # fn main() {
+/// stack_build!(let nums = numbers, 7);
+/// assert_eq!(*nums, [7, 8, 9]);
+/// # }
+/// ```
+#[macro_export]
+macro_rules! stack_build {
+    (let $name:ident = $helper:expr $(, $arg:expr)* $(,)?) => {
+        let mut _slot = ::std::mem::MaybeUninit::uninit();
+        let $name = ($helper)(&mut _slot $(, $arg)*);
+    };
+    (let mut $name:ident = $helper:expr $(, $arg:expr)* $(,)?) => {
+        let mut _slot = ::std::mem::MaybeUninit::uninit();
+        let mut $name = ($helper)(&mut _slot $(, $arg)*);
+    };
+}
+
+/// Pushes a value onto a `StackDynVec`, coercing it to the list's unsized element type. This is the stable counterpart of `StackDynVec::push`, and likewise evaluates to `Err(value)` if the list is full or its buffer has no room left.
+///
+/// ```
+/// # #[macro_use] extern crate stack_ptr;
+/// # use stack_ptr::vec::StackDynVec;
+/// # use std::fmt::Debug;
+/// # fn main() {
+/// StackDynVec::<dyn Debug, 2>::scope::<32, _, _>(|mut list| {
+///     stack_dyn_push!(list, 1u8).unwrap();
+///     stack_dyn_push!(list, "two").unwrap();
+///     assert_eq!(stack_dyn_push!(list, 3u16), Err(3));
+/// });
+/// # }
+/// ```
+#[macro_export]
+macro_rules! stack_dyn_push {
+    ($vec:expr, $value:expr) => {{
+        // evaluated outside the `unsafe` block, so the arguments can't use unsafe operations without `unsafe` of their own
+        let _vec = &mut $vec;
+        let _value = $value;
+        unsafe {
+            _vec.push_with(_value, |value| value)
+        }
+    }};
+}
+
+/// Defines a newtype around `StackPtr<dyn Trait>`, for giving a stack-allocated trait object a domain-specific name. The generated struct has a lifetime parameter for the `StackPtr`, a `new` constructor, `into_inner`, and `Deref` and `DerefMut` to the trait object. Traits listed in `impl` blocks, including `Debug`, are implemented by forwarding each listed method to the trait object; methods taking `&self` and `&mut self` are supported.
+///
+/// ```
+/// # #[macro_use] extern crate stack_ptr;
+/// use std::fmt::{self, Display, Formatter};
+///
+/// stack_newtype! {
+///     /// A message that is rendered with `Display`.
+///     pub struct Message(dyn Display);
+///
+///     impl Display {
+///         fn fmt(&self, f: &mut Formatter) -> fmt::Result;
+///     }
+/// }
+///
+/// # fn main() {
+/// declare_stackptr! {
+///     let text: StackPtr<dyn Display> = StackPtr::new(42);
+/// }
+/// let message = Message::new(text);
+/// assert_eq!(message.to_string(), "42");
+/// # }
+/// ```
+#[macro_export]
+macro_rules! stack_newtype {
+    (
+        $(#[$attr:meta])*
+        $vis:vis struct $name:ident(dyn $($bound:tt)+);
+        $(
+            impl $trait:path {
+                $(fn $method:ident $params:tt $(-> $ret:ty)?;)*
+            }
+        )*
+    ) => {
+        $(#[$attr])*
+        $vis struct $name<'a>($crate::StackPtr<'a, dyn $($bound)+ + 'a>);
+
+        impl<'a> $name<'a> {
+            /// Wraps a stack-allocated trait object.
+            #[allow(dead_code)]
+            $vis fn new(sp: $crate::StackPtr<'a, dyn $($bound)+ + 'a>) -> $name<'a> {
+                $name(sp)
+            }
+
+            /// Unwraps the underlying `StackPtr`.
+            #[allow(dead_code)]
+            $vis fn into_inner(self) -> $crate::StackPtr<'a, dyn $($bound)+ + 'a> {
+                self.0
+            }
+        }
+
+        impl<'a> ::std::ops::Deref for $name<'a> {
+            type Target = dyn $($bound)+ + 'a;
+
+            fn deref(&self) -> &(dyn $($bound)+ + 'a) {
+                &*self.0
+            }
+        }
+
+        impl<'a> ::std::ops::DerefMut for $name<'a> {
+            fn deref_mut(&mut self) -> &mut (dyn $($bound)+ + 'a) {
+                &mut *self.0
+            }
+        }
+
+        $(
+            impl<'a> $trait for $name<'a> {
+                $(stack_newtype!(@method $trait, $method $params $(-> $ret)?);)*
+            }
+        )*
+    };
+    (@method $trait:path, $method:ident (&self $(, $arg:ident: $argty:ty)* $(,)?) $(-> $ret:ty)?) => {
+        fn $method(&self $(, $arg: $argty)*) $(-> $ret)? {
+            <_ as $trait>::$method(&*self.0 $(, $arg)*)
+        }
+    };
+    (@method $trait:path, $method:ident (&mut self $(, $arg:ident: $argty:ty)* $(,)?) $(-> $ret:ty)?) => {
+        fn $method(&mut self $(, $arg: $argty)*) $(-> $ret)? {
+            <_ as $trait>::$method(&mut *self.0 $(, $arg)*)
+        }
+    };
+}
+
+/// Generates a value with `arbitrary::Arbitrary` and declares it on the stack as a `StackPtr`, for fuzzing code that consumes `StackPtr`s. The right-hand side is a `&mut arbitrary::Unstructured`; if generating the value fails, the error is returned from the enclosing function with `?`. Requires the `arbitrary` feature.
+///
+/// ```
+/// # #[macro_use] extern crate stack_ptr;
+/// # extern crate arbitrary;
+/// # fn main() {
+/// # fn run() -> arbitrary::Result<()> {
+/// let mut u = arbitrary::Unstructured::new(&[1, 2, 3, 4]);
+/// stack_arbitrary! {
+///     let value: StackPtr<u32> = &mut u;
+/// }
+/// assert_eq!(*value, u32::from_le_bytes([1, 2, 3, 4]));
+/// # Ok(())
+/// # }
+/// # run().unwrap();
+/// # }
+/// ```
+#[cfg(feature = "arbitrary")]
+#[macro_export]
+macro_rules! stack_arbitrary {
+    (let $name:ident: StackPtr<$ty:ty> = $u:expr;) => {
+        let mut _slot = ::std::mem::MaybeUninit::uninit();
+        let $name = $crate::StackPtr::<$ty>::from_value(&mut _slot, <$ty as $crate::arbitrary::Arbitrary>::arbitrary($u)?);
+    };
+    (let mut $name:ident: StackPtr<$ty:ty> = $u:expr;) => {
+        let mut _slot = ::std::mem::MaybeUninit::uninit();
+        let mut $name = $crate::StackPtr::<$ty>::from_value(&mut _slot, <$ty as $crate::arbitrary::Arbitrary>::arbitrary($u)?);
+    };
+}
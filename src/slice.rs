@@ -0,0 +1,552 @@
+use std::convert::TryFrom;
+use std::io::{IoSlice, IoSliceMut};
+use std::iter::Enumerate;
+use std::mem::{self, MaybeUninit};
+use std::ops::Range;
+use std::pin::Pin;
+use std::sync::atomic;
+use std::{ptr, slice};
+
+use super::StackPtr;
+
+impl<'a> StackPtr<'a, [u8]> {
+    /// Reinterprets an owned byte slice as an owned `T`, for zero-copy parsing of stack buffers. The length and alignment of the bytes are checked against `T` in debug builds, and the returned `StackPtr` takes over ownership of the storage.
+    ///
+    /// # Safety
+    ///
+    /// The slice must be exactly `size_of::<T>()` bytes long, its start must be suitably aligned for `T`, and the bytes must be a valid bit pattern for `T`. Once reinterpreted, the value is dropped as a `T`, so any invariants `T`'s destructor relies on must hold as well.
+    pub unsafe fn from_byte_slice<T>(sp: StackPtr<'a, [u8]>) -> StackPtr<'a, T> {
+        debug_assert_eq!(sp.len(), mem::size_of::<T>(), "byte slice length does not match the size of the target type");
+        debug_assert_eq!(sp.as_ptr() as usize % mem::align_of::<T>(), 0, "byte slice is not aligned for the target type");
+        let ptr = StackPtr::into_mut(sp).as_mut_ptr() as *mut T;
+        StackPtr::from_mut(&mut *ptr)
+    }
+
+    /// Narrows a receive buffer to the payload of a length-prefixed frame, keeping the first `payload_len` bytes. If the declared length is longer than the buffer, which usually means the frame is malformed or truncated, the buffer is handed back unchanged in `Err`.
+    pub fn narrow_to_payload(sp: StackPtr<'a, [u8]>, payload_len: usize) -> Result<StackPtr<'a, [u8]>, StackPtr<'a, [u8]>> {
+        if payload_len > sp.len() {
+            return Err(sp);
+        }
+
+        Ok(StackPtr::truncate(sp, payload_len))
+    }
+
+    /// Borrows the bytes as an `IoSlice`, so stack buffers can be passed to `Write::write_vectored` without copying them into a `Vec`.
+    pub fn as_io_slice(&self) -> IoSlice<'_> {
+        IoSlice::new(self)
+    }
+
+    /// Borrows the bytes as an `IoSliceMut`, for reading into stack buffers with `Read::read_vectored`.
+    pub fn as_io_slice_mut(&mut self) -> IoSliceMut<'_> {
+        IoSliceMut::new(self)
+    }
+}
+
+impl<'a, T> StackPtr<'a, [T]> {
+    /// Adopts an array declared by the caller as a `StackPtr<[T]>` of length `N`, tied to the lifetime of the borrow.
+    ///
+    /// # Safety
+    ///
+    /// The `StackPtr` becomes the sole owner of the elements and drops them, so the array must not be used or dropped again afterwards. Typically this means the array is wrapped in `ManuallyDrop`, or passed to `mem::forget` once the borrow ends.
+    pub unsafe fn from_array_ref<const N: usize>(arr: &'a mut [T; N]) -> StackPtr<'a, [T]> {
+        StackPtr::from_mut(arr)
+    }
+
+    /// Moves all elements of `a` followed by all elements of `b` into `buf`, returning a `StackPtr` over the combined elements. Both inputs are consumed without cloning. The `stack_concat!` macro declares a suitable buffer for you.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `buf` is too small to hold the elements of both inputs. No elements are moved in that case.
+    pub fn concat_into<'b, 'c>(buf: &'a mut [MaybeUninit<T>], a: StackPtr<'b, [T]>, b: StackPtr<'c, [T]>) -> StackPtr<'a, [T]> {
+        let len = a.len() + b.len();
+        assert!(len <= buf.len(), "combined length {} exceeds buffer capacity {}", len, buf.len());
+
+        let dst = buf.as_mut_ptr() as *mut T;
+        unsafe {
+            let a = StackPtr::into_mut(a);
+            let b = StackPtr::into_mut(b);
+            ptr::copy_nonoverlapping(a.as_ptr(), dst, a.len());
+            ptr::copy_nonoverlapping(b.as_ptr(), dst.add(a.len()), b.len());
+            StackPtr::from_mut(&mut *ptr::slice_from_raw_parts_mut(dst, len))
+        }
+    }
+
+    /// Moves the elements of `a` and `b` into `buf` alternately, starting with `a`, and returns a `StackPtr` over the combined elements. Both inputs are consumed without cloning. The `stack_interleave!` macro declares a suitable buffer for you.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `a` and `b` have different lengths, or if `buf` is too small to hold the elements of both. No elements are moved in that case.
+    pub fn interleave_into<'b, 'c>(buf: &'a mut [MaybeUninit<T>], a: StackPtr<'b, [T]>, b: StackPtr<'c, [T]>) -> StackPtr<'a, [T]> {
+        assert_eq!(a.len(), b.len(), "interleaved slices must have the same length");
+        let len = a.len() + b.len();
+        assert!(len <= buf.len(), "combined length {} exceeds buffer capacity {}", len, buf.len());
+
+        let dst = buf.as_mut_ptr() as *mut T;
+        unsafe {
+            let a = StackPtr::into_mut(a);
+            let b = StackPtr::into_mut(b);
+            for i in 0..a.len() {
+                ptr::copy_nonoverlapping(a.as_ptr().add(i), dst.add(2 * i), 1);
+                ptr::copy_nonoverlapping(b.as_ptr().add(i), dst.add(2 * i + 1), 1);
+            }
+            StackPtr::from_mut(slice::from_raw_parts_mut(dst, len))
+        }
+    }
+
+    /// Moves the elements of every `StackPtr<[T]>` yielded by `iter` into `buf`, in order, returning a `StackPtr` over all of them. Each input is consumed without cloning. The `stack_concat_iter!` macro declares a suitable buffer for you.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `buf` is too small to hold all the elements. The elements moved so far, the input that didn't fit and the rest of the iterator are all dropped in that case.
+    pub fn concat_iter_into<'b, I>(buf: &'a mut [MaybeUninit<T>], iter: I) -> StackPtr<'a, [T]>
+    where T: 'b, I: IntoIterator<Item = StackPtr<'b, [T]>> {
+        let capacity = buf.len();
+        let mut guard = InitGuard { data: buf.as_mut_ptr() as *mut T, len: 0 };
+        for piece in iter {
+            let len = guard.len + piece.len();
+            assert!(len <= capacity, "combined length {} exceeds buffer capacity {}", len, capacity);
+            unsafe {
+                let piece = StackPtr::into_mut(piece);
+                ptr::copy_nonoverlapping(piece.as_ptr(), guard.data.add(guard.len), piece.len());
+            }
+            guard.len = len;
+        }
+
+        let (data, len) = (guard.data, guard.len);
+        mem::forget(guard);
+        unsafe {
+            StackPtr::from_mut(slice::from_raw_parts_mut(data, len))
+        }
+    }
+
+    /// Fills every slot of `buf` with the value `f` returns for its index, in order, returning a `StackPtr` over the whole buffer. This is the owned-slice counterpart of `array::from_fn`; the `stack_from_fn!` macro declares the buffer for you.
+    ///
+    /// # Panics
+    ///
+    /// If `f` panics, the elements it has already returned are dropped before unwinding continues.
+    pub fn from_fn_into<F: FnMut(usize) -> T>(buf: &'a mut [MaybeUninit<T>], mut f: F) -> StackPtr<'a, [T]> {
+        let len = buf.len();
+        let mut guard = InitGuard { data: buf.as_mut_ptr() as *mut T, len: 0 };
+        while guard.len < len {
+            let value = f(guard.len);
+            unsafe {
+                guard.data.add(guard.len).write(value);
+            }
+            guard.len += 1;
+        }
+
+        let data = guard.data;
+        mem::forget(guard);
+        unsafe {
+            StackPtr::from_mut(slice::from_raw_parts_mut(data, len))
+        }
+    }
+
+    /// Fills the first `n` slots of `buf` with copies of `value`, returning a `StackPtr` over them. `value` itself is moved into the last slot, so it is cloned `n - 1` times, or dropped if `n` is 0. The `stack_repeat!` macro declares a suitable buffer for you.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `n` is greater than the length of `buf`. If a call to `clone` panics, the copies made so far are dropped.
+    pub fn repeat_into(buf: &'a mut [MaybeUninit<T>], value: T, n: usize) -> StackPtr<'a, [T]> where T: Clone {
+        assert!(n <= buf.len(), "repeat count {} exceeds buffer capacity {}", n, buf.len());
+        let mut guard = InitGuard { data: buf.as_mut_ptr() as *mut T, len: 0 };
+        if n > 0 {
+            while guard.len < n - 1 {
+                unsafe {
+                    guard.data.add(guard.len).write(value.clone());
+                }
+                guard.len += 1;
+            }
+            unsafe {
+                guard.data.add(guard.len).write(value);
+            }
+            guard.len += 1;
+        }
+
+        let data = guard.data;
+        mem::forget(guard);
+        unsafe {
+            StackPtr::from_mut(slice::from_raw_parts_mut(data, n))
+        }
+    }
+
+    /// Moves each element into `true_buf` or `false_buf` depending on what `pred` returns for its index and value, and returns `StackPtr`s over the filled prefixes of the two buffers. Elements keep their relative order, and each one is moved exactly once, without touching the heap; see `partition` for a version that collects into `Vec`s.
+    ///
+    /// # Panics
+    ///
+    /// Panics if an element doesn't fit in the buffer `pred` picks for it. Everything is dropped in that case, or if `pred` panics: the elements already moved, the one being placed, and the ones not yet visited.
+    pub fn partition_into<'b, 'c, F: FnMut(usize, &T) -> bool>(sp: StackPtr<'a, [T]>, mut pred: F, true_buf: &'b mut [MaybeUninit<T>], false_buf: &'c mut [MaybeUninit<T>]) -> (StackPtr<'b, [T]>, StackPtr<'c, [T]>) {
+        let capacities = (true_buf.len(), false_buf.len());
+        let mut true_guard = InitGuard { data: true_buf.as_mut_ptr() as *mut T, len: 0 };
+        let mut false_guard = InitGuard { data: false_buf.as_mut_ptr() as *mut T, len: 0 };
+        for (i, value) in sp.into_iter().enumerate() {
+            let (guard, capacity, name) = if pred(i, &value) {
+                (&mut true_guard, capacities.0, "true")
+            } else {
+                (&mut false_guard, capacities.1, "false")
+            };
+            assert!(guard.len < capacity, "partition_into: {} buffer capacity {} exceeded", name, capacity);
+            unsafe {
+                guard.data.add(guard.len).write(value);
+            }
+            guard.len += 1;
+        }
+
+        let (true_data, true_len) = (true_guard.data, true_guard.len);
+        let (false_data, false_len) = (false_guard.data, false_guard.len);
+        mem::forget(true_guard);
+        mem::forget(false_guard);
+        unsafe {
+            (
+                StackPtr::from_mut(slice::from_raw_parts_mut(true_data, true_len)),
+                StackPtr::from_mut(slice::from_raw_parts_mut(false_data, false_len)),
+            )
+        }
+    }
+
+    /// Consumes a `StackPtr<[T]>` without dropping it, returning the data pointer and the length, for passing the slice to C or other code that takes the two separately. The caller becomes responsible for the elements: they must be dropped, for example by passing both values back to `from_raw_slice`, or they are leaked.
+    pub fn into_raw_slice(sp: StackPtr<'a, [T]>) -> (*mut T, usize) {
+        let slice = StackPtr::into_mut(sp);
+        (slice.as_mut_ptr(), slice.len())
+    }
+
+    /// Reconstructs a `StackPtr<[T]>` from a data pointer and length, as returned by `into_raw_slice`. The lifetime is unbounded, so the caller should pin it down, e.g. with a type annotation; `'static` is only right for storage that lives for the rest of the program.
+    ///
+    /// # Safety
+    ///
+    /// `data` must point to `len` valid, initialized elements that stay alive for `'a` and that nothing else will use or drop, since the `StackPtr` takes over ownership of them. In particular, C code that was handed the slice must be done with it. See `validate_raw_slice` for a debug check of the pointer itself.
+    pub unsafe fn from_raw_slice(data: *mut T, len: usize) -> StackPtr<'a, [T]> {
+        StackPtr::from_mut(slice::from_raw_parts_mut(data, len))
+    }
+
+    /// Drops the elements, then overwrites their storage with zeros, for wiping secrets such as keys or passwords held in a stack buffer. The zeros are written with `ptr::write_volatile` followed by a compiler fence, so the compiler can't drop the writes as dead stores even though the storage is never read again. This works for any `T`, including `u8` and other `Copy` types, whose drop does nothing.
+    ///
+    /// Only the storage the `StackPtr` points to is wiped. Copies the value made earlier, such as the original that was moved into a `StackPtr` or heap data owned by the elements, are not affected.
+    pub fn drop_and_zero(sp: StackPtr<'a, [T]>) {
+        // a single raw pointer is used for both the drop and the wipe, since reborrowing the slice for `drop_in_place` would invalidate a pointer taken from it earlier
+        let raw: *mut [T] = StackPtr::into_mut(sp);
+        unsafe {
+            let size = mem::size_of_val(&*raw);
+            ptr::drop_in_place(raw);
+            let data = raw as *mut u8;
+            for i in 0..size {
+                ptr::write_volatile(data.add(i), 0);
+            }
+        }
+        atomic::compiler_fence(atomic::Ordering::SeqCst);
+    }
+
+    /// Reinterprets the elements as a slice of `U`, keeping the same bytes. The new length is the old length in bytes divided by `size_of::<U>()`; in debug builds, the byte length is checked to be a multiple of that size and the data to be aligned for `U`. The returned `StackPtr` drops the data as `[U]`, so `T`'s destructor doesn't run.
+    ///
+    /// # Safety
+    ///
+    /// The byte length must be a multiple of `size_of::<U>()` and the data must be aligned for `U`, which is only checked in debug builds. Every group of bytes must be a valid `U`, so `T` must not contain padding or uninitialized bytes where `U` needs initialized ones, and any invariants of `U` must hold. `U` must not be a zero-sized type.
+    pub unsafe fn transmute_slice<U>(sp: StackPtr<'a, [T]>) -> StackPtr<'a, [U]> {
+        let byte_len = mem::size_of_val(&*sp);
+        debug_assert!(mem::size_of::<U>() != 0, "cannot transmute to a slice of zero-sized type");
+        debug_assert_eq!(byte_len % mem::size_of::<U>(), 0, "byte length is not a multiple of the target element size");
+        debug_assert_eq!(sp.as_ptr() as usize % mem::align_of::<U>(), 0, "slice is not aligned for the target element type");
+        let data = StackPtr::into_mut(sp).as_mut_ptr() as *mut U;
+        StackPtr::from_mut(slice::from_raw_parts_mut(data, byte_len / mem::size_of::<U>()))
+    }
+
+    /// Converts a slice of exactly one element into a `StackPtr` to that element. Slices of any other length are handed back in `Err`.
+    pub fn into_single(sp: StackPtr<'a, [T]>) -> Result<StackPtr<'a, T>, StackPtr<'a, [T]>> {
+        if sp.len() != 1 {
+            return Err(sp);
+        }
+
+        unsafe {
+            let elem = StackPtr::into_mut(sp).as_mut_ptr();
+            Ok(StackPtr::from_mut(&mut *elem))
+        }
+    }
+
+    /// Removes consecutive repeated elements, dropping the duplicates and moving the kept elements to the front. Returns a `StackPtr` over the kept elements, which reuses the same storage with a shorter length.
+    pub fn dedup(sp: StackPtr<'a, [T]>) -> StackPtr<'a, [T]> where T: PartialEq {
+        unsafe {
+            let elems = StackPtr::into_mut(sp);
+            let data = elems.as_mut_ptr();
+            let len = compact(data, elems.len(), |kept, elem| kept.last() == Some(elem), drop);
+            StackPtr::from_mut(slice::from_raw_parts_mut(data, len))
+        }
+    }
+
+    /// Moves out every element for which `pred` returns `true`, returning them in a `Vec` together with a `StackPtr` over the rest, which are compacted to the front of the same storage in their original order. `pred` can mutate the elements it inspects. Each element is moved exactly once, either into the `Vec` or within the storage.
+    ///
+    /// The removed elements are collected on the heap, since the storage can't hold them once the kept elements are moved over them. If `pred` panics, the elements already removed and the ones still in the storage are all dropped.
+    pub fn into_drain_filter<F: FnMut(&mut T) -> bool>(sp: StackPtr<'a, [T]>, mut pred: F) -> (Vec<T>, StackPtr<'a, [T]>) {
+        let mut removed = Vec::new();
+        unsafe {
+            let elems = StackPtr::into_mut(sp);
+            let data = elems.as_mut_ptr();
+            let len = compact(data, elems.len(), |_, elem| pred(elem), |elem| removed.push(elem));
+            (removed, StackPtr::from_mut(slice::from_raw_parts_mut(data, len)))
+        }
+    }
+
+    /// Drops the elements past `len` and returns a `StackPtr` over the first `len` elements, reusing the same storage. If `len` is at least the current length, the `StackPtr` is returned unchanged.
+    pub fn truncate(sp: StackPtr<'a, [T]>, len: usize) -> StackPtr<'a, [T]> {
+        if len >= sp.len() {
+            return sp;
+        }
+
+        unsafe {
+            let (head, tail) = StackPtr::into_mut(sp).split_at_mut(len);
+            let head = StackPtr::from_mut(head);
+            ptr::drop_in_place(tail);
+            head
+        }
+    }
+
+    /// Moves out the element at `idx`, replacing it with the last element, and returns it together with a `StackPtr` over the remaining elements. This doesn't preserve the order of the elements, but it is O(1).
+    ///
+    /// # Panics
+    ///
+    /// Panics if `idx` is out of bounds. The `StackPtr` is dropped as a whole in that case.
+    pub fn swap_remove(sp: StackPtr<'a, [T]>, idx: usize) -> (T, StackPtr<'a, [T]>) {
+        let len = sp.len();
+        assert!(idx < len, "swap_remove index {} out of bounds for length {}", idx, len);
+
+        unsafe {
+            let data = StackPtr::into_mut(sp).as_mut_ptr();
+            let removed = ptr::read(data.add(idx));
+            if idx != len - 1 {
+                ptr::copy_nonoverlapping(data.add(len - 1), data.add(idx), 1);
+            }
+            (removed, StackPtr::from_mut(slice::from_raw_parts_mut(data, len - 1)))
+        }
+    }
+
+    /// Keeps only the elements in `range`, dropping the ones before and after it, and returns a `StackPtr` over the kept elements, reusing the same storage.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the start of `range` is greater than its end, or its end is greater than the length of the slice. The `StackPtr` is dropped as a whole in that case.
+    pub fn narrow(sp: StackPtr<'a, [T]>, range: Range<usize>) -> StackPtr<'a, [T]> {
+        assert!(range.start <= range.end && range.end <= sp.len(), "range {:?} out of bounds for length {}", range, sp.len());
+
+        unsafe {
+            let (rest, tail) = StackPtr::into_mut(sp).split_at_mut(range.end);
+            let (head, kept) = rest.split_at_mut(range.start);
+            let kept = StackPtr::from_mut(kept);
+            let tail = StackPtr::from_mut(tail);
+            drop(StackPtr::from_mut(head));
+            drop(tail);
+            kept
+        }
+    }
+
+    /// Splits the slice around the first element matching `pred`, returning owned `StackPtr`s over the elements before and after it. The matching element itself is dropped. If no element matches, the whole slice is returned as the first half and the second half is `None`.
+    pub fn split_once<F: FnMut(&T) -> bool>(sp: StackPtr<'a, [T]>, pred: F) -> (StackPtr<'a, [T]>, Option<StackPtr<'a, [T]>>) {
+        let index = match sp.iter().position(pred) {
+            Some(index) => index,
+            None => return (sp, None),
+        };
+
+        unsafe {
+            let (head, rest) = StackPtr::into_mut(sp).split_at_mut(index);
+            let (separator, tail) = rest.split_first_mut().expect("position returned an in-bounds index");
+            let head = StackPtr::from_mut(head);
+            let tail = StackPtr::from_mut(tail);
+            ptr::drop_in_place(separator);
+            (head, Some(tail))
+        }
+    }
+
+    /// Projects a pinned `StackPtr<[T]>` to a pinned slice, for driving a stack-allocated array of futures or other `!Unpin` values without boxing each one.
+    ///
+    /// This takes the `StackPtr` itself pinned, e.g. with `std::pin::pin!`, rather than `&mut StackPtr`: with a plain `&mut`, the elements could be moved out through the `StackPtr` once the borrow ends. Pinning the `StackPtr` keeps it from being moved, but not from being forgotten, for instance inside a `Pin<Box<StackPtr>>` passed to `mem::forget`, and its storage is reclaimed when its stack frame returns whether or not the elements were dropped.
+    ///
+    /// # Safety
+    ///
+    /// The `StackPtr` must be dropped, rather than forgotten or leaked, before its storage goes out of scope or is reused, so that the pinning drop guarantee holds for the elements. Pinning it on the stack with `std::pin::pin!` in the frame that owns the storage ensures this.
+    pub unsafe fn as_pin_mut_slice<'b>(sp: Pin<&'b mut StackPtr<'a, [T]>>) -> Pin<&'b mut [T]> {
+        sp.map_unchecked_mut(|sp| &mut **sp)
+    }
+
+    /// Reverses the order of the elements in place. Forwards to `<[T]>::reverse`.
+    pub fn reverse(&mut self) {
+        (**self).reverse()
+    }
+
+    /// Rotates the slice in place so that the element at `mid` becomes the first element. Forwards to `<[T]>::rotate_left`.
+    pub fn rotate_left(&mut self, mid: usize) {
+        (**self).rotate_left(mid)
+    }
+
+    /// Rotates the slice in place so that the last `k` elements move to the front. Forwards to `<[T]>::rotate_right`.
+    pub fn rotate_right(&mut self, k: usize) {
+        (**self).rotate_right(k)
+    }
+
+    /// Fills the slice with clones of `value`, dropping the previous elements. Forwards to `<[T]>::fill`.
+    pub fn fill(&mut self, value: T) where T: Clone {
+        (**self).fill(value)
+    }
+
+    /// Fills the slice with values returned by calling `f` repeatedly, dropping the previous elements. Forwards to `<[T]>::fill_with`.
+    pub fn fill_with<F: FnMut() -> T>(&mut self, f: F) {
+        (**self).fill_with(f)
+    }
+
+    /// Returns the address of element `idx`, for checking where elements live, for instance that `split_once` or `narrow` keep them in place. The index is checked in debug builds only; in release builds an out-of-bounds index yields a dangling address, which is safe to return but must not be dereferenced.
+    pub fn addr_of_elem(&self, idx: usize) -> *const T {
+        debug_assert!(idx < self.len(), "index {} out of bounds for length {}", idx, self.len());
+        self.as_ptr().wrapping_add(idx)
+    }
+
+    /// Returns `true` if the memory of the two slices intersects. Two owning `StackPtr`s should never overlap, so this is a debugging aid for catching aliasing introduced by misuse of the unsafe constructors. It takes plain slices, so a `&StackPtr<[T]>` can be passed directly, as can borrowed subslices of one. Empty slices don't overlap anything.
+    pub fn overlaps(a: &[T], b: &[T]) -> bool {
+        let range = |s: &[T]| {
+            let start = s.as_ptr() as usize;
+            (start, start + mem::size_of_val(s))
+        };
+        let (a_start, a_end) = range(a);
+        let (b_start, b_end) = range(b);
+        a_start < a_end && b_start < b_end && a_start < b_end && b_start < a_end
+    }
+
+    /// Returns `true` if the elements are sorted in ascending order. Forwards to `<[T]>::is_sorted`.
+    pub fn is_sorted(&self) -> bool where T: PartialOrd {
+        (**self).is_sorted()
+    }
+
+    /// Borrows the slice as an array of length `N`, or returns `None` if the length doesn't match.
+    pub fn as_array<const N: usize>(&self) -> Option<&[T; N]> {
+        <&[T; N]>::try_from(&**self).ok()
+    }
+
+    /// Mutably borrows the slice as an array of length `N`, or returns `None` if the length doesn't match.
+    pub fn as_array_mut<const N: usize>(&mut self) -> Option<&mut [T; N]> {
+        <&mut [T; N]>::try_from(&mut **self).ok()
+    }
+
+    /// Returns an iterator over mutable references to the elements along with their indices, for in-place passes that need each element's position. Equivalent to `iter_mut().enumerate()`.
+    pub fn iter_mut_enumerate(&mut self) -> Enumerate<slice::IterMut<'_, T>> {
+        (**self).iter_mut().enumerate()
+    }
+
+    /// Returns a borrowing iterator over all overlapping windows of `size` elements. Forwards to `<[T]>::windows`.
+    pub fn windows(&self, size: usize) -> slice::Windows<'_, T> {
+        (**self).windows(size)
+    }
+
+    /// Returns a borrowing iterator over `size` elements at a time, with the last chunk possibly shorter. Forwards to `<[T]>::chunks`; see `StackPtr::into_chunks` for the owning version.
+    pub fn chunks(&self, size: usize) -> slice::Chunks<'_, T> {
+        (**self).chunks(size)
+    }
+
+    /// Returns a borrowing iterator over exactly `size` elements at a time. The elements left over at the end are available from the iterator's `remainder`. Forwards to `<[T]>::chunks_exact`.
+    pub fn chunks_exact(&self, size: usize) -> slice::ChunksExact<'_, T> {
+        (**self).chunks_exact(size)
+    }
+
+    /// Returns a borrowing iterator over `size` elements at a time, starting from the end, with the last chunk (the front of the slice) possibly shorter. Forwards to `<[T]>::rchunks`.
+    pub fn rchunks(&self, size: usize) -> slice::RChunks<'_, T> {
+        (**self).rchunks(size)
+    }
+
+    /// Borrows the slice as two disjoint mutable halves, `[0, mid)` and `[mid, len)`, for in-place divide-and-conquer. Unlike the owning splits, the `StackPtr` keeps ownership of all the elements. Forwards to `<[T]>::split_at_mut`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `mid` is greater than the length.
+    pub fn split_at_mut_borrow(&mut self, mid: usize) -> (&mut [T], &mut [T]) {
+        (**self).split_at_mut(mid)
+    }
+
+    /// Returns `true` if `prefix` is a prefix of the slice. Forwards to `<[T]>::starts_with`.
+    pub fn starts_with(&self, prefix: &[T]) -> bool where T: PartialEq {
+        (**self).starts_with(prefix)
+    }
+
+    /// Returns `true` if `suffix` is a suffix of the slice. Forwards to `<[T]>::ends_with`.
+    pub fn ends_with(&self, suffix: &[T]) -> bool where T: PartialEq {
+        (**self).ends_with(suffix)
+    }
+}
+
+impl<'a, T, const N: usize> StackPtr<'a, [T; N]> {
+    /// Collects exactly `N` items from `iter` into `slot`, returning a `StackPtr` over the resulting array. The `stack_collect_exact!` macro declares the slot for you.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the iterator yields fewer or more than `N` items. The items collected so far, and the extra item if there is one, are dropped before unwinding continues.
+    pub fn collect_exact<I: IntoIterator<Item = T>>(slot: &'a mut MaybeUninit<[T; N]>, iter: I) -> StackPtr<'a, [T; N]> {
+        let mut guard = InitGuard { data: slot.as_mut_ptr() as *mut T, len: 0 };
+        let mut iter = iter.into_iter();
+        while guard.len < N {
+            match iter.next() {
+                Some(value) => unsafe {
+                    guard.data.add(guard.len).write(value);
+                    guard.len += 1;
+                },
+                None => panic!("iterator yielded {} items, expected exactly {}", guard.len, N),
+            }
+        }
+        if iter.next().is_some() {
+            panic!("iterator yielded more than {} items", N);
+        }
+
+        mem::forget(guard);
+        unsafe {
+            StackPtr::from_mut(slot.assume_init_mut())
+        }
+    }
+}
+
+/// Owns the first `len` elements at `data` while a slice is being filled in place, and drops them if filling is cut short by a panic.
+struct InitGuard<T> {
+    data: *mut T,
+    len: usize,
+}
+
+impl<T> Drop for InitGuard<T> {
+    fn drop(&mut self) {
+        unsafe {
+            ptr::drop_in_place(ptr::slice_from_raw_parts_mut(self.data, self.len));
+        }
+    }
+}
+
+/// Moves the elements for which `remove` returns `false` to the front of the `len` elements at `data`, in order, passing each removed element to `removed` by value. Returns the number of kept elements. If either callback panics, every element that is still live is dropped, so nothing is leaked or dropped twice.
+///
+/// The caller must own the elements, and must treat only the returned prefix as initialized afterwards.
+unsafe fn compact<T, R, F>(data: *mut T, len: usize, mut remove: R, mut removed: F) -> usize
+where R: FnMut(&[T], &mut T) -> bool, F: FnMut(T) {
+    struct Guard<T> {
+        data: *mut T,
+        len: usize,
+        kept: usize,
+        read: usize,
+    }
+
+    impl<T> Drop for Guard<T> {
+        fn drop(&mut self) {
+            unsafe {
+                ptr::drop_in_place(ptr::slice_from_raw_parts_mut(self.data, self.kept));
+                ptr::drop_in_place(ptr::slice_from_raw_parts_mut(self.data.add(self.read), self.len - self.read));
+            }
+        }
+    }
+
+    let mut guard = Guard { data, len, kept: 0, read: 0 };
+    while guard.read < len {
+        let elem = data.add(guard.read);
+        if remove(slice::from_raw_parts(data, guard.kept), &mut *elem) {
+            guard.read += 1;
+            removed(ptr::read(elem));
+        } else {
+            if guard.read != guard.kept {
+                ptr::copy_nonoverlapping(elem, data.add(guard.kept), 1);
+            }
+            guard.kept += 1;
+            guard.read += 1;
+        }
+    }
+
+    let kept = guard.kept;
+    mem::forget(guard);
+    kept
+}
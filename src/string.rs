@@ -0,0 +1,102 @@
+use std::fmt::{Debug, Display, Formatter};
+use std::fmt;
+use std::mem::MaybeUninit;
+use std::ops::Deref;
+use std::{ptr, slice, str};
+
+use super::StackPtr;
+
+/// A string builder with a fixed capacity of `N` bytes, writing into a caller-provided stack buffer. Only whole `&str`s and `char`s are ever appended, so the written prefix is always valid UTF-8, and it can be turned into a `StackPtr<str>` with `into_stackptr`.
+pub struct StackString<'a, const N: usize> {
+    buf: &'a mut [MaybeUninit<u8>; N],
+    len: usize,
+}
+
+impl<'a, const N: usize> StackString<'a, N> {
+    /// Creates an empty `StackString` that writes into `buf`.
+    pub fn new(buf: &'a mut [MaybeUninit<u8>; N]) -> StackString<'a, N> {
+        StackString {
+            buf,
+            len: 0,
+        }
+    }
+
+    /// Returns the length of the string in bytes.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Returns `true` if nothing has been written yet.
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Returns the total number of bytes the string can hold.
+    pub fn capacity(&self) -> usize {
+        N
+    }
+
+    /// Returns the number of bytes that can still be written before the buffer is full.
+    pub fn remaining_capacity(&self) -> usize {
+        N - self.len
+    }
+
+    /// Returns the string written so far.
+    pub fn as_str(&self) -> &str {
+        unsafe {
+            str::from_utf8_unchecked(slice::from_raw_parts(self.buf.as_ptr() as *const u8, self.len))
+        }
+    }
+
+    /// Appends a string slice, or hands it back in `Err` if it doesn't fit. Nothing is written in that case.
+    pub fn push_str<'s>(&mut self, s: &'s str) -> Result<(), &'s str> {
+        if s.len() > self.remaining_capacity() {
+            return Err(s);
+        }
+
+        unsafe {
+            ptr::copy_nonoverlapping(s.as_ptr(), self.buf.as_mut_ptr().add(self.len) as *mut u8, s.len());
+        }
+        self.len += s.len();
+        Ok(())
+    }
+
+    /// Appends a character, or hands it back in `Err` if its UTF-8 encoding doesn't fit.
+    pub fn push(&mut self, ch: char) -> Result<(), char> {
+        self.push_str(ch.encode_utf8(&mut [0; 4])).map_err(|_| ch)
+    }
+
+    /// Consumes the builder, returning a `StackPtr` over the written bytes. The unused part of the buffer is left uninitialized.
+    pub fn into_stackptr(self) -> StackPtr<'a, str> {
+        unsafe {
+            let bytes = slice::from_raw_parts_mut(self.buf.as_mut_ptr() as *mut u8, self.len);
+            StackPtr::from_mut(str::from_utf8_unchecked_mut(bytes))
+        }
+    }
+}
+
+impl<'a, const N: usize> Deref for StackString<'a, N> {
+    type Target = str;
+
+    fn deref(&self) -> &str {
+        self.as_str()
+    }
+}
+
+impl<'a, const N: usize> fmt::Write for StackString<'a, N> {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        self.push_str(s).map_err(|_| fmt::Error)
+    }
+}
+
+impl<'a, const N: usize> Debug for StackString<'a, N> {
+    fn fmt(&self, formatter: &mut Formatter) -> fmt::Result {
+        Debug::fmt(self.as_str(), formatter)
+    }
+}
+
+impl<'a, const N: usize> Display for StackString<'a, N> {
+    fn fmt(&self, formatter: &mut Formatter) -> fmt::Result {
+        Display::fmt(self.as_str(), formatter)
+    }
+}
@@ -0,0 +1,18 @@
+use std::thread;
+
+use super::StackPtr;
+
+/// Moves a `StackPtr` to a scoped thread, runs `f` on it there, and returns the result once the thread has finished. Because the thread is joined before this returns, the `'a` storage stays valid for as long as the other thread can use it. The value is dropped exactly once, by whichever side ends up owning the `StackPtr`: `f` can drop it on the other thread or return it to get it back.
+///
+/// # Panics
+///
+/// If `f` panics, the panic is propagated to the caller once the thread has been joined.
+pub fn transfer_scoped<'a, T, R, F>(sp: StackPtr<'a, T>, f: F) -> R
+where T: 'a + Send + ?Sized, R: Send, F: FnOnce(StackPtr<'a, T>) -> R + Send {
+    thread::scope(|scope| {
+        match scope.spawn(move || f(sp)).join() {
+            Ok(result) => result,
+            Err(payload) => std::panic::resume_unwind(payload),
+        }
+    })
+}
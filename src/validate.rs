@@ -0,0 +1,55 @@
+use std::mem;
+
+use super::StackPtr;
+
+// Best-effort sanity checks for pointers that are about to be, or have been, turned into a `StackPtr` through the unsafe constructors. They only run in debug builds and always return `Ok(())` in release builds, so they are meant for `debug_assert!` and tests rather than as a safety net.
+
+impl<'a, T: ?Sized> StackPtr<'a, T> {
+    /// Checks that the pointer is aligned for the pointee and that the pointee doesn't extend past the end of the address space. Returns a description of the first problem found. Always returns `Ok(())` in release builds.
+    pub fn validate(sp: &StackPtr<'a, T>) -> Result<(), &'static str> {
+        if cfg!(debug_assertions) {
+            check(&**sp as *const T as *const u8 as usize, mem::align_of_val(&**sp), Some(mem::size_of_val(&**sp)))
+        } else {
+            Ok(())
+        }
+    }
+}
+
+impl<'a, T> StackPtr<'a, T> {
+    /// Checks a raw pointer before it is passed to `from_raw_parts` or `from_mut`: it must be non-null, aligned for `T`, and `T` must not extend past the end of the address space. Only the pointer's address is inspected, so this is safe to call on any pointer. Always returns `Ok(())` in release builds.
+    pub fn validate_raw(ptr: *const T) -> Result<(), &'static str> {
+        if cfg!(debug_assertions) {
+            check(ptr as usize, mem::align_of::<T>(), Some(mem::size_of::<T>()))
+        } else {
+            Ok(())
+        }
+    }
+}
+
+impl<'a, T> StackPtr<'a, [T]> {
+    /// Checks the data pointer and length of a slice before they are used to build a `StackPtr<[T]>`: the pointer must be non-null and aligned for `T`, the total size must fit in an `isize`, and the slice must not extend past the end of the address space. Only the address is inspected, so this is safe to call on any pointer. Always returns `Ok(())` in release builds.
+    pub fn validate_raw_slice(data: *const T, len: usize) -> Result<(), &'static str> {
+        if cfg!(debug_assertions) {
+            check(data as usize, mem::align_of::<T>(), len.checked_mul(mem::size_of::<T>()))
+        } else {
+            Ok(())
+        }
+    }
+}
+
+fn check(addr: usize, align: usize, size: Option<usize>) -> Result<(), &'static str> {
+    if addr == 0 {
+        return Err("pointer is null");
+    }
+    if !addr.is_multiple_of(align) {
+        return Err("pointer is not aligned for the pointee type");
+    }
+    let size = match size {
+        Some(size) if size <= isize::MAX as usize => size,
+        _ => return Err("pointee size overflows isize"),
+    };
+    if addr.checked_add(size).is_none() {
+        return Err("pointee extends past the end of the address space");
+    }
+    Ok(())
+}
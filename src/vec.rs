@@ -0,0 +1,260 @@
+use std::fmt::{Debug, Formatter};
+use std::fmt;
+#[cfg(feature = "nightly")]
+use std::marker::Unsize;
+use std::mem::{self, ManuallyDrop, MaybeUninit};
+use std::ops::{Deref, DerefMut};
+use std::{array, iter, ptr, slice};
+
+use super::StackPtr;
+
+/// A vector with a fixed capacity whose elements live in a caller-provided stack buffer, in the style of `ArrayVec`. Elements can be pushed until the buffer is full, and the initialized prefix can be turned into a `StackPtr<[T]>` with `into_stackptr`.
+pub struct StackVec<'a, T: 'a> {
+    buf: StackPtr<'a, [MaybeUninit<T>]>,
+    len: usize,
+}
+
+impl<'a, T> StackVec<'a, T> {
+    /// Creates an empty `StackVec` that stores its elements in `buf`. The capacity is the length of `buf`.
+    pub fn new(buf: &'a mut [MaybeUninit<T>]) -> StackVec<'a, T> {
+        StackVec {
+            buf: unsafe { StackPtr::from_mut(buf) },
+            len: 0,
+        }
+    }
+
+    /// Returns the number of elements in the vector.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Returns `true` if the vector contains no elements.
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Returns the total number of elements the vector can hold.
+    pub fn capacity(&self) -> usize {
+        self.buf.len()
+    }
+
+    /// Returns the number of elements that can still be pushed before the vector is full.
+    pub fn remaining_capacity(&self) -> usize {
+        self.capacity() - self.len
+    }
+
+    /// Appends an element to the back of the vector, or hands it back in `Err` if the vector is full.
+    pub fn push(&mut self, value: T) -> Result<(), T> {
+        if self.len == self.capacity() {
+            return Err(value);
+        }
+
+        self.buf[self.len] = MaybeUninit::new(value);
+        self.len += 1;
+        Ok(())
+    }
+
+    /// Consumes the vector, returning a `StackPtr` that owns its elements. The unused part of the buffer is left uninitialized.
+    pub fn into_stackptr(self) -> StackPtr<'a, [T]> {
+        let this = ManuallyDrop::new(self);
+        unsafe {
+            let buf = StackPtr::into_mut(ptr::read(&this.buf));
+            StackPtr::from_mut(slice::from_raw_parts_mut(buf.as_mut_ptr() as *mut T, this.len))
+        }
+    }
+}
+
+impl<'a, T> Drop for StackVec<'a, T> {
+    fn drop(&mut self) {
+        unsafe {
+            ptr::drop_in_place(&mut **self)
+        }
+    }
+}
+
+impl<'a, T> Deref for StackVec<'a, T> {
+    type Target = [T];
+
+    fn deref(&self) -> &[T] {
+        unsafe {
+            slice::from_raw_parts(self.buf.as_ptr() as *const T, self.len)
+        }
+    }
+}
+
+impl<'a, T> DerefMut for StackVec<'a, T> {
+    fn deref_mut(&mut self) -> &mut [T] {
+        unsafe {
+            slice::from_raw_parts_mut(self.buf.as_mut_ptr() as *mut T, self.len)
+        }
+    }
+}
+
+impl<'a, T> Debug for StackVec<'a, T> where T: Debug {
+    fn fmt(&self, formatter: &mut Formatter) -> fmt::Result {
+        self.deref().fmt(formatter)
+    }
+}
+
+/// A last-in, first-out stack holding up to `N` elements in a caller-provided stack buffer. Unlike `StackVec`, only the top element can be accessed. Elements still on the stack when it is dropped are dropped from the top down.
+pub struct StackStack<'a, T: 'a, const N: usize> {
+    buf: StackPtr<'a, [MaybeUninit<T>]>,
+    top: usize,
+}
+
+impl<'a, T, const N: usize> StackStack<'a, T, N> {
+    /// Creates an empty `StackStack` that stores its elements in `buf`.
+    pub fn new(buf: &'a mut [MaybeUninit<T>; N]) -> StackStack<'a, T, N> {
+        StackStack {
+            buf: unsafe { StackPtr::from_mut(buf as &mut [MaybeUninit<T>]) },
+            top: 0,
+        }
+    }
+
+    /// Returns the number of elements on the stack.
+    pub fn len(&self) -> usize {
+        self.top
+    }
+
+    /// Returns `true` if the stack contains no elements.
+    pub fn is_empty(&self) -> bool {
+        self.top == 0
+    }
+
+    /// Returns the total number of elements the stack can hold, which is always `N`.
+    pub fn capacity(&self) -> usize {
+        N
+    }
+
+    /// Pushes an element onto the top of the stack, or hands it back in `Err` if the stack is full.
+    pub fn push(&mut self, value: T) -> Result<(), T> {
+        if self.top == N {
+            return Err(value);
+        }
+
+        self.buf[self.top] = MaybeUninit::new(value);
+        self.top += 1;
+        Ok(())
+    }
+
+    /// Removes the top element and returns it, or `None` if the stack is empty.
+    pub fn pop(&mut self) -> Option<T> {
+        if self.top == 0 {
+            return None;
+        }
+
+        self.top -= 1;
+        Some(unsafe { self.buf[self.top].assume_init_read() })
+    }
+
+    /// Returns a reference to the top element without removing it, or `None` if the stack is empty.
+    pub fn peek(&self) -> Option<&T> {
+        if self.top == 0 {
+            return None;
+        }
+
+        Some(unsafe { self.buf[self.top - 1].assume_init_ref() })
+    }
+}
+
+impl<'a, T, const N: usize> Drop for StackStack<'a, T, N> {
+    fn drop(&mut self) {
+        while self.pop().is_some() {}
+    }
+}
+
+/// A fixed-capacity list of up to `N` values of different concrete types, each stored on the stack and coerced to the common unsized type `Dyn`, such as `dyn FnMut()` or `dyn Debug`. The values are laid out one after another in a byte buffer, and each one is kept as a `StackPtr<Dyn>`.
+///
+/// A `StackDynVec` is created with `scope`, which declares the buffer on its own stack frame and lends the empty list to a closure. Values are added with `push` on nightly, or with the `stack_dyn_push!` macro on stable, and are dropped in the order they were pushed.
+pub struct StackDynVec<'a, Dyn: 'a + ?Sized, const N: usize> {
+    rest: &'a mut [MaybeUninit<u8>],
+    entries: [Option<StackPtr<'a, Dyn>>; N],
+    len: usize,
+}
+
+impl<'a, Dyn: ?Sized, const N: usize> StackDynVec<'a, Dyn, N> {
+    /// Declares a buffer of `BYTES` bytes on the stack and calls `f` with an empty `StackDynVec` that stores its values there. The list and everything pushed to it must be used up or dropped before `f` returns.
+    ///
+    /// ```
+    /// # #[macro_use] extern crate stack_ptr;
+    /// # use stack_ptr::vec::StackDynVec;
+    /// # fn main() {
+    /// let total = StackDynVec::<dyn Fn() -> i32, 2>::scope::<64, _, _>(|mut fns| {
+    ///     let offset = 10;
+    ///     stack_dyn_push!(fns, || 1).unwrap_or_else(|_| panic!("full"));
+    ///     stack_dyn_push!(fns, move || offset).unwrap_or_else(|_| panic!("full"));
+    ///     fns.into_iter().map(|f| f()).sum::<i32>()
+    /// });
+    /// assert_eq!(total, 11);
+    /// # }
+    /// ```
+    pub fn scope<const BYTES: usize, R, F>(f: F) -> R
+    where F: for<'b> FnOnce(StackDynVec<'b, Dyn, N>) -> R {
+        let mut buf = [MaybeUninit::uninit(); BYTES];
+        f(StackDynVec {
+            rest: &mut buf,
+            entries: array::from_fn(|_| None),
+            len: 0,
+        })
+    }
+
+    /// Returns the number of values in the list.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Returns `true` if the list contains no values.
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Returns the maximum number of values the list can hold.
+    pub fn capacity(&self) -> usize {
+        N
+    }
+
+    /// Moves `value` into the buffer and appends it to the list as a `Dyn`, or hands it back in `Err` if the list is full or the buffer doesn't have room for it.
+    #[cfg(feature = "nightly")]
+    pub fn push<T: 'a + Unsize<Dyn>>(&mut self, value: T) -> Result<(), T> {
+        unsafe {
+            self.push_with(value, |value| value)
+        }
+    }
+
+    /// Implementation detail of `push` and `stack_dyn_push!`.
+    ///
+    /// # Safety
+    ///
+    /// `coerce` must return its argument, converted to `Dyn` by an unsizing coercion.
+    #[doc(hidden)]
+    pub unsafe fn push_with<T>(&mut self, value: T, coerce: fn(&'a mut T) -> &'a mut Dyn) -> Result<(), T> {
+        if self.len == N {
+            return Err(value);
+        }
+
+        let offset = self.rest.as_ptr().align_offset(mem::align_of::<T>());
+        match offset.checked_add(mem::size_of::<T>()) {
+            Some(end) if end <= self.rest.len() => {},
+            _ => return Err(value),
+        }
+
+        let rest = mem::take(&mut self.rest);
+        let (slot, rest) = rest[offset..].split_at_mut(mem::size_of::<T>());
+        self.rest = rest;
+
+        let slot = &mut *(slot.as_mut_ptr() as *mut MaybeUninit<T>);
+        let value = coerce(StackPtr::into_mut(StackPtr::from_value(slot, value)));
+        self.entries[self.len] = Some(StackPtr::from_mut(value));
+        self.len += 1;
+        Ok(())
+    }
+}
+
+impl<'a, Dyn: ?Sized, const N: usize> IntoIterator for StackDynVec<'a, Dyn, N> {
+    type Item = StackPtr<'a, Dyn>;
+    type IntoIter = iter::Flatten<array::IntoIter<Option<StackPtr<'a, Dyn>>, N>>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        IntoIterator::into_iter(self.entries).flatten()
+    }
+}
@@ -0,0 +1,90 @@
+#[macro_use]
+extern crate stack_ptr;
+
+use stack_ptr::StackPtr;
+use stack_ptr::any::StackAnyMap;
+
+#[test]
+fn test_any_map() {
+    declare_stackptr!{
+        let number = StackPtr::new(5u32);
+    }
+    declare_stackptr!{
+        let name = StackPtr::new(String::from("config"));
+    }
+
+    let mut map = StackAnyMap::<4>::new();
+    assert!(map.is_empty());
+    assert!(map.insert(number).unwrap().is_none());
+    assert!(map.insert(name).unwrap().is_none());
+    assert_eq!(map.len(), 2);
+
+    assert_eq!(map.get::<u32>(), Some(&5));
+    assert_eq!(map.get::<String>().map(|s| &s[..]), Some("config"));
+    assert_eq!(map.get::<i64>(), None);
+
+    map.get_mut::<String>().unwrap().push_str("ured");
+    let name = map.remove::<String>().unwrap();
+    assert_eq!(&*name, "configured");
+    assert!(map.remove::<String>().is_none());
+    assert_eq!(map.len(), 1);
+}
+
+#[test]
+fn test_any_map_replace_and_full() {
+    declare_stackptr!{
+        let first = StackPtr::new(1u8);
+    }
+    declare_stackptr!{
+        let second = StackPtr::new(2u8);
+    }
+    declare_stackptr!{
+        let other = StackPtr::new(3u16);
+    }
+
+    let mut map = StackAnyMap::<1>::new();
+    assert!(map.insert(first).unwrap().is_none());
+    let old = map.insert(second).unwrap().unwrap();
+    assert_eq!(*old, 1u8);
+    assert_eq!(map.get::<u8>(), Some(&2));
+
+    let rejected = map.insert(other).unwrap_err();
+    assert_eq!(*rejected, 3u16);
+}
+
+#[test]
+fn test_into_any_round_trip() {
+    declare_stackptr!{
+        let number = StackPtr::new(7i32);
+    }
+
+    let erased = StackPtr::into_any(number);
+    assert!(erased.is::<i32>());
+
+    let erased = StackPtr::downcast::<String>(erased).unwrap_err();
+    let number = StackPtr::downcast::<i32>(erased).unwrap();
+    assert_eq!(*number, 7);
+}
+
+#[test]
+fn test_downcast_unchecked() {
+    declare_stackptr!{
+        let name = StackPtr::new(String::from("known"));
+    }
+
+    let erased = StackPtr::into_any(name);
+    let name = unsafe { StackPtr::downcast_unchecked::<String>(erased) };
+    assert_eq!(&*name, "known");
+}
+
+#[cfg(debug_assertions)]
+#[test]
+#[should_panic(expected = "wrong type")]
+fn test_downcast_unchecked_wrong_type() {
+    declare_stackptr!{
+        let number = StackPtr::new(1u8);
+    }
+
+    let erased = StackPtr::into_any(number);
+    let _ = unsafe { StackPtr::downcast_unchecked::<u16>(erased) };
+}
@@ -0,0 +1,34 @@
+#![cfg(feature = "arbitrary")]
+
+extern crate arbitrary;
+#[macro_use]
+extern crate stack_ptr;
+
+use arbitrary::Unstructured;
+
+fn sum_pair(u: &mut Unstructured) -> arbitrary::Result<u32> {
+    stack_arbitrary! {
+        let pair: StackPtr<(u8, u8)> = u;
+    }
+    Ok(pair.0 as u32 + pair.1 as u32)
+}
+
+#[test]
+fn test_stack_arbitrary() {
+    let mut u = Unstructured::new(&[3, 4]);
+    assert_eq!(sum_pair(&mut u).unwrap(), 7);
+}
+
+#[test]
+fn test_stack_arbitrary_mut() {
+    fn run(u: &mut Unstructured) -> arbitrary::Result<Vec<u8>> {
+        stack_arbitrary! {
+            let mut bytes: StackPtr<[u8; 3]> = u;
+        }
+        bytes.reverse();
+        Ok(bytes.to_vec())
+    }
+
+    let mut u = Unstructured::new(&[1, 2, 3]);
+    assert_eq!(run(&mut u).unwrap(), [3, 2, 1]);
+}
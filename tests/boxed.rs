@@ -0,0 +1,95 @@
+#[macro_use]
+extern crate stack_ptr;
+
+mod common;
+
+use std::cell::Cell;
+use std::mem::MaybeUninit;
+
+use common::DropCounter;
+use stack_ptr::StackPtr;
+use stack_ptr::boxed::StackBoxedSlice;
+
+#[test]
+fn test_slice_api() {
+    declare_stackptr!{
+        let mut array = StackPtr::new([3, 1, 2]);
+    }
+    let mut slice = StackBoxedSlice::from(coerce_stackptr!(array, [i32]));
+
+    assert_eq!(slice.len(), 3);
+    assert!(!slice.is_empty());
+    assert_eq!(slice.iter().sum::<i32>(), 6);
+
+    slice.sort();
+    for elem in &mut slice {
+        *elem *= 10;
+    }
+    assert_eq!(&*slice, &[10, 20, 30]);
+    assert_eq!(format!("{:?}", slice), "[10, 20, 30]");
+    assert_eq!(slice.into_iter().collect::<Vec<_>>(), vec![10, 20, 30]);
+}
+
+#[test]
+fn test_boxed_slice_round_trip() {
+    let drops = Cell::new(0);
+    let boxed: Box<[DropCounter]> = vec![DropCounter(&drops), DropCounter(&drops)].into_boxed_slice();
+
+    let mut buf = [const { MaybeUninit::uninit() }; 4];
+    let slice = StackBoxedSlice::from_boxed_slice(boxed, &mut buf);
+    assert_eq!(slice.len(), 2);
+    assert_eq!(drops.get(), 0);
+
+    let boxed = slice.into_boxed_slice();
+    assert_eq!(boxed.len(), 2);
+    assert_eq!(drops.get(), 0);
+
+    drop(boxed);
+    assert_eq!(drops.get(), 2);
+}
+
+#[test]
+#[should_panic(expected = "exceeds buffer capacity")]
+fn test_from_boxed_slice_too_small() {
+    let boxed: Box<[i32]> = Box::new([1, 2, 3]);
+    let mut buf = [MaybeUninit::uninit(); 2];
+    StackBoxedSlice::from_boxed_slice(boxed, &mut buf);
+}
+
+#[test]
+fn test_spill_to_arc() {
+    use std::sync::Arc;
+
+    let drops = Cell::new(0);
+    declare_stackptr!{
+        let value = StackPtr::new((String::from("shared"), DropCounter(&drops)));
+    }
+
+    let arc = StackPtr::spill_to_arc(value);
+    assert_eq!(arc.0, "shared");
+    assert_eq!(drops.get(), 0);
+
+    let other = Arc::clone(&arc);
+    drop(arc);
+    assert_eq!(drops.get(), 0);
+    drop(other);
+    assert_eq!(drops.get(), 1);
+}
+
+#[test]
+fn test_spill_slice_to_arc() {
+    let drops = Cell::new(0);
+    declare_stackptr!{
+        let numbers: StackPtr<[i32]> = StackPtr::new([1, 2, 3]);
+    }
+    assert_eq!(&*StackPtr::spill_slice_to_arc(numbers), &[1, 2, 3]);
+
+    declare_stackptr!{
+        let counters: StackPtr<[DropCounter]> = StackPtr::new([DropCounter(&drops), DropCounter(&drops)]);
+    }
+    let arc = StackPtr::spill_slice_to_arc(counters);
+    assert_eq!(arc.len(), 2);
+    assert_eq!(drops.get(), 0);
+    drop(arc);
+    assert_eq!(drops.get(), 2);
+}
@@ -0,0 +1,70 @@
+#[macro_use]
+extern crate stack_ptr;
+
+use std::fmt::{Debug, Display};
+use std::io::Read;
+
+use stack_ptr::StackPtr;
+
+#[test]
+fn test_into_debug() {
+    declare_stackptr!{
+        let value = StackPtr::new(vec![Some(1), None]);
+    }
+
+    let value: StackPtr<dyn Debug> = StackPtr::into_debug(value);
+    assert_eq!(format!("{:?}", value), "[Some(1), None]");
+}
+
+#[test]
+fn test_into_display() {
+    declare_stackptr!{
+        let value = StackPtr::new(1.5);
+    }
+
+    let value: StackPtr<dyn Display> = StackPtr::into_display(value);
+    assert_eq!(value.to_string(), "1.5");
+}
+
+#[test]
+fn test_into_iterator() {
+    declare_stackptr!{
+        let iter = StackPtr::new((1..4).map(|x| x * 2));
+    }
+
+    let iter = StackPtr::into_iterator(iter);
+    assert_eq!(iter.collect::<Vec<i32>>(), vec![2, 4, 6]);
+}
+
+#[test]
+fn test_into_read() {
+    declare_stackptr!{
+        let reader = StackPtr::new(&b"data"[..]);
+    }
+
+    let mut reader = StackPtr::into_read(reader);
+    let mut out = String::new();
+    reader.read_to_string(&mut out).unwrap();
+    assert_eq!(out, "data");
+}
+
+#[test]
+fn test_coerce_stackptr_drops_auto_trait() {
+    declare_stackptr!{
+        let value: StackPtr<dyn Debug + Send> = StackPtr::new("text");
+    }
+
+    let value = coerce_stackptr!(value, dyn Debug);
+    assert_eq!(format!("{:?}", value), "\"text\"");
+}
+
+#[test]
+fn test_coerce_stackptr_drops_send_sync() {
+    declare_stackptr!{
+        let shared: StackPtr<dyn Display + Send + Sync> = StackPtr::new(3.5);
+    }
+
+    let shared = coerce_stackptr!(shared, dyn Display + Send);
+    let local: StackPtr<dyn Display> = coerce_stackptr!(shared, dyn Display);
+    assert_eq!(local.to_string(), "3.5");
+}
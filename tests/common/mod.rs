@@ -0,0 +1,32 @@
+#![allow(dead_code)]
+
+use std::cell::Cell;
+
+/// Increments the shared counter when dropped, so tests can check that every element is dropped exactly once.
+#[derive(Debug)]
+pub struct DropCounter<'a>(pub &'a Cell<usize>);
+
+impl<'a> Drop for DropCounter<'a> {
+    fn drop(&mut self) {
+        self.0.set(self.0.get() + 1);
+    }
+}
+
+/// A value that also counts its drops, compared by value only.
+#[derive(Debug)]
+pub struct Counted<'a> {
+    pub value: i32,
+    _counter: DropCounter<'a>,
+}
+
+impl<'a> Counted<'a> {
+    pub fn new(value: i32, drops: &'a Cell<usize>) -> Counted<'a> {
+        Counted { value, _counter: DropCounter(drops) }
+    }
+}
+
+impl<'a> PartialEq for Counted<'a> {
+    fn eq(&self, other: &Counted<'a>) -> bool {
+        self.value == other.value
+    }
+}
@@ -0,0 +1,38 @@
+#![cfg(feature = "executor")]
+
+#[macro_use]
+extern crate stack_ptr;
+
+use std::future::Future;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+
+use stack_ptr::executor::block_on_stack;
+
+struct YieldOnce {
+    yielded: bool,
+    value: i32,
+}
+
+impl Future for YieldOnce {
+    type Output = i32;
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context) -> Poll<i32> {
+        if self.yielded {
+            Poll::Ready(self.value)
+        } else {
+            self.yielded = true;
+            cx.waker().wake_by_ref();
+            Poll::Pending
+        }
+    }
+}
+
+#[test]
+fn test_block_on_stack() {
+    declare_stackptr!{
+        let fut: StackPtr<dyn Future<Output = i32>> = StackPtr::new(YieldOnce { yielded: false, value: 9 });
+    }
+
+    assert_eq!(block_on_stack(fut), 9);
+}
@@ -0,0 +1,50 @@
+#[macro_use]
+extern crate stack_ptr;
+
+use std::cell::RefCell;
+
+use stack_ptr::StackPtr;
+use stack_ptr::guard::OnDrop;
+
+struct Resource<'a> {
+    log: &'a RefCell<Vec<String>>,
+    uses: u32,
+}
+
+impl<'a> Drop for Resource<'a> {
+    fn drop(&mut self) {
+        self.log.borrow_mut().push("resource dropped".to_string());
+    }
+}
+
+#[test]
+fn test_on_drop_order() {
+    let log = RefCell::new(Vec::new());
+    declare_stackptr!{
+        let resource = StackPtr::new(Resource { log: &log, uses: 0 });
+    }
+
+    let mut guard = StackPtr::on_drop(resource, |resource| {
+        resource.log.borrow_mut().push(format!("closing after {} uses", resource.uses));
+    });
+    guard.uses += 2;
+    assert!(log.borrow().is_empty());
+
+    drop(guard);
+    assert_eq!(*log.borrow(), ["closing after 2 uses", "resource dropped"]);
+}
+
+#[test]
+fn test_into_inner() {
+    let log = RefCell::new(Vec::new());
+    declare_stackptr!{
+        let resource = StackPtr::new(Resource { log: &log, uses: 0 });
+    }
+
+    let guard = StackPtr::on_drop(resource, |_| panic!("guard should not run"));
+    let resource = OnDrop::into_inner(guard);
+    assert!(log.borrow().is_empty());
+
+    drop(resource);
+    assert_eq!(*log.borrow(), ["resource dropped"]);
+}
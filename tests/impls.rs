@@ -0,0 +1,227 @@
+#[macro_use]
+extern crate stack_ptr;
+
+use std::collections::hash_map::DefaultHasher;
+use std::error::Error;
+use std::fmt::{self, Debug, Display};
+use std::future::{self, Future};
+use std::hash::{Hash, Hasher};
+use std::io::{Read, Write};
+use std::pin::Pin;
+use std::task::{Context, Poll, Waker};
+
+#[derive(Debug)]
+struct Inner;
+
+impl Display for Inner {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("inner")
+    }
+}
+
+impl Error for Inner {}
+
+#[derive(Debug)]
+struct Outer(Inner);
+
+impl Display for Outer {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("outer")
+    }
+}
+
+impl Error for Outer {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        Some(&self.0)
+    }
+}
+
+#[test]
+fn test_iterator() {
+    declare_stackptr!{
+        let iter: StackPtr<dyn Iterator<Item = i32>> = StackPtr::new(vec![1,2,3].into_iter());
+    }
+
+    assert_eq!(iter.size_hint(), (3, Some(3)));
+    assert_eq!(iter.collect::<Vec<_>>(), [1,2,3]);
+}
+
+#[test]
+fn test_read() {
+    declare_stackptr!{
+        let mut reader: StackPtr<dyn Read> = StackPtr::new(&b"hello"[..]);
+    }
+
+    let mut s = String::new();
+    reader.read_to_string(&mut s).unwrap();
+    assert_eq!(s, "hello");
+}
+
+#[test]
+fn test_write() {
+    let mut out = Vec::new();
+    {
+        declare_stackptr!{
+            let mut writer: StackPtr<dyn Write> = StackPtr::new(&mut out);
+        }
+
+        write!(writer, "{}-{}", 1, 2).unwrap();
+        writer.flush().unwrap();
+    }
+    assert_eq!(out, b"1-2");
+}
+
+#[test]
+fn test_future() {
+    declare_stackptr!{
+        let mut fut: StackPtr<dyn Future<Output = i32> + Unpin> = StackPtr::new(future::ready(5));
+    }
+
+    let mut cx = Context::from_waker(Waker::noop());
+    assert_eq!(Pin::new(&mut fut).poll(&mut cx), Poll::Ready(5));
+}
+
+#[test]
+fn test_display() {
+    declare_stackptr!{
+        let value: StackPtr<dyn Display> = StackPtr::new(42);
+    }
+
+    assert_eq!(value.to_string(), "42");
+}
+
+#[test]
+fn test_debug() {
+    declare_stackptr!{
+        let value: StackPtr<dyn Debug> = StackPtr::new("hi");
+    }
+
+    assert_eq!(format!("{:?}", value), "\"hi\"");
+}
+
+#[test]
+fn test_error() {
+    declare_stackptr!{
+        let err: StackPtr<dyn Error> = StackPtr::new(Outer(Inner));
+    }
+
+    assert_eq!(err.to_string(), "outer");
+    assert_eq!(err.source().unwrap().to_string(), "inner");
+}
+
+#[test]
+fn test_hasher() {
+    declare_stackptr!{
+        let mut hasher: StackPtr<dyn Hasher> = StackPtr::new(DefaultHasher::new());
+    }
+
+    let mut expected = DefaultHasher::new();
+    "key".hash(&mut expected);
+    "key".hash(&mut hasher);
+    assert_eq!(hasher.finish(), expected.finish());
+}
+
+#[test]
+fn test_hasher_forwards_overrides() {
+    struct Custom(u64);
+
+    impl Hasher for Custom {
+        fn finish(&self) -> u64 {
+            self.0
+        }
+
+        fn write(&mut self, bytes: &[u8]) {
+            self.0 += bytes.len() as u64;
+        }
+
+        fn write_u64(&mut self, _: u64) {
+            self.0 += 7;
+        }
+    }
+
+    declare_stackptr!{
+        let mut hasher: StackPtr<dyn Hasher> = StackPtr::new(Custom(0));
+    }
+
+    hasher.write_u64(1);
+    assert_eq!(hasher.finish(), 7);
+}
+
+#[test]
+fn test_write_forwards_overrides() {
+    struct AllAtOnce(Vec<u8>);
+
+    impl Write for AllAtOnce {
+        fn write(&mut self, _: &[u8]) -> std::io::Result<usize> {
+            Ok(0)
+        }
+
+        fn write_all(&mut self, buf: &[u8]) -> std::io::Result<()> {
+            self.0.extend_from_slice(buf);
+            Ok(())
+        }
+
+        fn flush(&mut self) -> std::io::Result<()> {
+            Ok(())
+        }
+    }
+
+    let mut out = AllAtOnce(Vec::new());
+    {
+        declare_stackptr!{
+            let mut writer: StackPtr<dyn Write> = StackPtr::new(&mut out);
+        }
+
+        writer.write_all(b"abc").unwrap();
+    }
+    assert_eq!(out.0, b"abc");
+}
+
+#[test]
+fn test_slice_eq() {
+    declare_stackptr!{
+        let slice: StackPtr<[i32]> = StackPtr::new([1,2,3]);
+    }
+
+    assert_eq!(slice, [1,2,3]);
+    assert_eq!(slice, &[1,2,3]);
+    assert_eq!(slice, &[1,2,3][..]);
+    assert_eq!(slice, *vec![1,2,3]);
+    assert!(slice != [1,2]);
+    assert!(slice != [1,2,4][..]);
+}
+
+#[test]
+fn test_str_eq() {
+    use std::mem::MaybeUninit;
+    use stack_ptr::string::StackString;
+
+    let mut buf = [MaybeUninit::uninit(); 8];
+    let mut string = StackString::new(&mut buf);
+    string.push_str("hello").unwrap();
+    let sp = string.into_stackptr();
+
+    assert_eq!(sp, "hello");
+    assert_eq!(sp, *"hello");
+    assert!(sp != "help");
+}
+
+#[cfg(unix)]
+#[test]
+fn test_as_fd() {
+    use std::fs::File;
+    use std::os::unix::io::{AsFd, AsRawFd};
+
+    let file = File::open(file!()).unwrap();
+    let raw = file.as_raw_fd();
+
+    declare_stackptr!{
+        let fd: StackPtr<dyn AsFd> = StackPtr::new(file);
+    }
+    assert_eq!(fd.as_fd().as_raw_fd(), raw);
+
+    declare_stackptr!{
+        let raw_fd: StackPtr<dyn AsRawFd> = StackPtr::new(File::open(file!()).unwrap());
+    }
+    assert!(raw_fd.as_raw_fd() >= 0);
+}
@@ -0,0 +1,411 @@
+#[macro_use]
+extern crate stack_ptr;
+
+mod common;
+
+use std::cell::Cell;
+
+use common::DropCounter;
+use stack_ptr::StackPtr;
+
+#[test]
+fn test_peek() {
+    declare_stackptr!{
+        let slice: StackPtr<[i32]> = StackPtr::new([1,2,3]);
+    }
+
+    let mut iter = slice.into_iter();
+    assert_eq!(iter.peek(), Some(&1));
+    assert_eq!(iter.peek(), Some(&1));
+    assert_eq!(iter.next(), Some(1));
+    assert_eq!(iter.peek(), Some(&2));
+    assert_eq!(iter.next(), Some(2));
+    assert_eq!(iter.next(), Some(3));
+    assert_eq!(iter.peek(), None);
+}
+
+#[test]
+fn test_peek_then_drop() {
+    let drops = Cell::new(0);
+    declare_stackptr!{
+        let slice: StackPtr<[DropCounter]> = StackPtr::new([DropCounter(&drops), DropCounter(&drops)]);
+    }
+
+    let iter = slice.into_iter();
+    assert!(iter.peek().is_some());
+    drop(iter);
+    assert_eq!(drops.get(), 2);
+}
+
+#[test]
+fn test_into_chunks_even() {
+    declare_stackptr!{
+        let slice: StackPtr<[i32]> = StackPtr::new([1,2,3,4,5,6]);
+    }
+
+    let mut chunks = StackPtr::into_chunks(slice, 2);
+    assert_eq!(&*chunks.next().unwrap(), &[1,2]);
+    assert_eq!(&*chunks.next().unwrap(), &[3,4]);
+    assert_eq!(&*chunks.next().unwrap(), &[5,6]);
+    assert!(chunks.next().is_none());
+}
+
+#[test]
+fn test_into_chunks_uneven() {
+    let drops = Cell::new(0);
+    declare_stackptr!{
+        let slice: StackPtr<[DropCounter]> = StackPtr::new([
+            DropCounter(&drops), DropCounter(&drops), DropCounter(&drops),
+            DropCounter(&drops), DropCounter(&drops),
+        ]);
+    }
+
+    let mut chunks = StackPtr::into_chunks(slice, 3);
+    let first = chunks.next().unwrap();
+    assert_eq!(first.len(), 3);
+    drop(first);
+    assert_eq!(drops.get(), 3);
+
+    // the remaining two-element chunk is dropped by the iterator
+    drop(chunks);
+    assert_eq!(drops.get(), 5);
+}
+
+#[test]
+#[should_panic]
+fn test_into_chunks_zero() {
+    declare_stackptr!{
+        let slice: StackPtr<[i32]> = StackPtr::new([1,2,3]);
+    }
+
+    StackPtr::into_chunks(slice, 0);
+}
+
+struct Tagged<'a> {
+    tag: i32,
+    _counter: DropCounter<'a>,
+}
+
+#[test]
+fn test_find_drops_once() {
+    let drops = Cell::new(0);
+    declare_stackptr!{
+        let slice: StackPtr<[Tagged]> = StackPtr::new([
+            Tagged { tag: 1, _counter: DropCounter(&drops) },
+            Tagged { tag: 2, _counter: DropCounter(&drops) },
+            Tagged { tag: 3, _counter: DropCounter(&drops) },
+            Tagged { tag: 4, _counter: DropCounter(&drops) },
+        ]);
+    }
+
+    let mut iter = slice.into_iter();
+    let found = iter.find(|t| t.tag == 2).unwrap();
+    // the element visited before the match is dropped by `find`
+    assert_eq!(drops.get(), 1);
+
+    drop(iter);
+    assert_eq!(drops.get(), 3);
+
+    assert_eq!(found.tag, 2);
+    drop(found);
+    assert_eq!(drops.get(), 4);
+}
+
+#[test]
+fn test_position_drops_once() {
+    let drops = Cell::new(0);
+    declare_stackptr!{
+        let slice: StackPtr<[Tagged]> = StackPtr::new([
+            Tagged { tag: 1, _counter: DropCounter(&drops) },
+            Tagged { tag: 2, _counter: DropCounter(&drops) },
+            Tagged { tag: 3, _counter: DropCounter(&drops) },
+        ]);
+    }
+
+    let mut iter = slice.into_iter();
+    assert_eq!(iter.position(|t| t.tag == 2), Some(1));
+    assert_eq!(drops.get(), 2);
+
+    drop(iter);
+    assert_eq!(drops.get(), 3);
+}
+
+#[test]
+fn test_into_iter_rev() {
+    let drops = Cell::new(0);
+    declare_stackptr!{
+        let slice: StackPtr<[Tagged]> = StackPtr::new([
+            Tagged { tag: 1, _counter: DropCounter(&drops) },
+            Tagged { tag: 2, _counter: DropCounter(&drops) },
+            Tagged { tag: 3, _counter: DropCounter(&drops) },
+        ]);
+    }
+
+    let mut iter = StackPtr::into_iter_rev(slice);
+    assert_eq!(iter.next().map(|t| t.tag), Some(3));
+    assert_eq!(iter.next().map(|t| t.tag), Some(2));
+    assert_eq!(drops.get(), 2);
+
+    drop(iter);
+    assert_eq!(drops.get(), 3);
+}
+
+#[test]
+fn test_double_ended() {
+    declare_stackptr!{
+        let slice: StackPtr<[i32]> = StackPtr::new([1,2,3,4]);
+    }
+
+    let mut iter = slice.into_iter();
+    assert_eq!(iter.next_back(), Some(4));
+    assert_eq!(iter.next(), Some(1));
+    assert_eq!(iter.collect::<Vec<_>>(), vec![2, 3]);
+}
+
+#[test]
+fn test_into_iter_indexed() {
+    let drops = Cell::new(0);
+    declare_stackptr!{
+        let slice: StackPtr<[Tagged]> = StackPtr::new([
+            Tagged { tag: 10, _counter: DropCounter(&drops) },
+            Tagged { tag: 20, _counter: DropCounter(&drops) },
+            Tagged { tag: 30, _counter: DropCounter(&drops) },
+            Tagged { tag: 40, _counter: DropCounter(&drops) },
+        ]);
+    }
+
+    let mut iter = StackPtr::into_iter_indexed(slice);
+    let (i, first) = iter.next().unwrap();
+    assert_eq!((i, first.tag), (0, 10));
+    let (i, second) = iter.next().unwrap();
+    assert_eq!((i, second.tag), (1, 20));
+    assert_eq!(drops.get(), 0);
+
+    drop(iter);
+    assert_eq!(drops.get(), 2);
+    drop((first, second));
+    assert_eq!(drops.get(), 4);
+}
+
+#[test]
+fn test_array_into_iter_both_ends() {
+    let drops = Cell::new(0);
+    declare_stackptr!{
+        let array = StackPtr::new([
+            Tagged { tag: 1, _counter: DropCounter(&drops) },
+            Tagged { tag: 2, _counter: DropCounter(&drops) },
+            Tagged { tag: 3, _counter: DropCounter(&drops) },
+            Tagged { tag: 4, _counter: DropCounter(&drops) },
+            Tagged { tag: 5, _counter: DropCounter(&drops) },
+        ]);
+    }
+
+    let mut iter = array.into_iter();
+    assert_eq!(iter.len(), 5);
+    assert_eq!(iter.next().map(|t| t.tag), Some(1));
+    assert_eq!(iter.next_back().map(|t| t.tag), Some(5));
+    assert_eq!(iter.next_back().map(|t| t.tag), Some(4));
+    assert_eq!(iter.len(), 2);
+    assert_eq!(drops.get(), 3);
+
+    drop(iter);
+    assert_eq!(drops.get(), 5);
+}
+
+#[test]
+fn test_into_fold() {
+    declare_stackptr!{
+        let words: StackPtr<[String]> = StackPtr::new([
+            String::from("stack"),
+            String::from("-"),
+            String::from("ptr"),
+        ]);
+    }
+
+    let joined = StackPtr::into_fold(words, String::new(), |mut acc, word| {
+        acc.push_str(&word);
+        acc
+    });
+    assert_eq!(joined, "stack-ptr");
+}
+
+#[test]
+fn test_for_loop_break() {
+    let drops = Cell::new(0);
+    declare_stackptr!{
+        let slice: StackPtr<[Tagged]> = StackPtr::new([
+            Tagged { tag: 1, _counter: DropCounter(&drops) },
+            Tagged { tag: 2, _counter: DropCounter(&drops) },
+            Tagged { tag: 3, _counter: DropCounter(&drops) },
+            Tagged { tag: 4, _counter: DropCounter(&drops) },
+            Tagged { tag: 5, _counter: DropCounter(&drops) },
+        ]);
+    }
+
+    let mut seen = 0;
+    for tagged in slice {
+        seen += 1;
+        // each yielded element is dropped at the end of its iteration
+        assert_eq!(drops.get(), seen - 1);
+        if tagged.tag == 2 {
+            break;
+        }
+    }
+    // two dropped by the loop body, three by the iterator
+    assert_eq!(seen, 2);
+    assert_eq!(drops.get(), 5);
+}
+
+#[test]
+fn test_remaining() {
+    declare_stackptr!{
+        let slice: StackPtr<[i32]> = StackPtr::new([1,2,3]);
+    }
+
+    let mut iter = slice.into_iter();
+    assert_eq!(iter.remaining(), 3);
+    iter.next();
+    assert_eq!(iter.remaining(), 2);
+    iter.next_back();
+    assert_eq!(iter.remaining(), 1);
+    iter.next();
+    assert_eq!(iter.remaining(), 0);
+    assert_eq!(iter.next(), None);
+    assert_eq!(iter.remaining(), 0);
+}
+
+#[test]
+fn test_partition() {
+    declare_stackptr!{
+        let numbers: StackPtr<[i32]> = StackPtr::new([1,2,3,4,5,6,7]);
+    }
+
+    let (evens, odds) = StackPtr::partition(numbers, |n| n % 2 == 0);
+    assert_eq!(evens, vec![2, 4, 6]);
+    assert_eq!(odds, vec![1, 3, 5, 7]);
+}
+
+#[test]
+fn test_partition_drops() {
+    let drops = Cell::new(0);
+    declare_stackptr!{
+        let slice: StackPtr<[Tagged]> = StackPtr::new([
+            Tagged { tag: 1, _counter: DropCounter(&drops) },
+            Tagged { tag: 2, _counter: DropCounter(&drops) },
+            Tagged { tag: 3, _counter: DropCounter(&drops) },
+        ]);
+    }
+
+    let (small, large) = StackPtr::partition(slice, |t| t.tag < 2);
+    assert_eq!((small.len(), large.len()), (1, 2));
+    assert_eq!(drops.get(), 0);
+    drop((small, large));
+    assert_eq!(drops.get(), 3);
+}
+
+#[test]
+fn test_into_elem_ptrs() {
+    let drops = Cell::new(0);
+    declare_stackptr!{
+        let slice: StackPtr<[Tagged]> = StackPtr::new([
+            Tagged { tag: 1, _counter: DropCounter(&drops) },
+            Tagged { tag: 2, _counter: DropCounter(&drops) },
+            Tagged { tag: 3, _counter: DropCounter(&drops) },
+            Tagged { tag: 4, _counter: DropCounter(&drops) },
+            Tagged { tag: 5, _counter: DropCounter(&drops) },
+        ]);
+    }
+
+    let mut iter = StackPtr::into_elem_ptrs(slice);
+    let first = iter.next().unwrap();
+    let last = iter.next_back().unwrap();
+    drop(iter.next().unwrap());
+    assert_eq!((first.tag, last.tag), (1, 5));
+    assert_eq!(drops.get(), 1);
+
+    // only the two elements that were never yielded are dropped by the iterator
+    drop(iter);
+    assert_eq!(drops.get(), 3);
+
+    drop((first, last));
+    assert_eq!(drops.get(), 5);
+}
+
+#[test]
+fn test_flatten() {
+    let drops = Cell::new(0);
+    declare_stackptr!{
+        let slice: StackPtr<[Tagged]> = StackPtr::new([
+            Tagged { tag: 1, _counter: DropCounter(&drops) },
+            Tagged { tag: 2, _counter: DropCounter(&drops) },
+        ]);
+    }
+
+    let mut elems = StackPtr::into_elem_ptrs(slice);
+    let (first, second) = (elems.next().unwrap(), elems.next().unwrap());
+    drop(elems);
+    declare_stackptr!{
+        let nested: StackPtr<[StackPtr<Tagged>]> = StackPtr::new([first, second]);
+    }
+
+    let mut flat = StackPtr::flatten(nested);
+    let first = flat.next().unwrap();
+    assert_eq!(first.tag, 1);
+    assert_eq!(drops.get(), 0);
+
+    // the second inner pointer was never yielded, so the iterator drops it and its pointee
+    drop(flat);
+    assert_eq!(drops.get(), 1);
+    drop(first);
+    assert_eq!(drops.get(), 2);
+}
+
+#[test]
+fn test_into_array_chunks_even() {
+    declare_stackptr!{
+        let slice: StackPtr<[i32]> = StackPtr::new([1,2,3,4,5,6]);
+    }
+
+    let mut chunks = StackPtr::into_array_chunks::<3>(slice);
+    assert_eq!(chunks.size_hint(), (2, Some(2)));
+    assert_eq!(*chunks.next().unwrap(), [1,2,3]);
+    assert_eq!(*chunks.next().unwrap(), [4,5,6]);
+    assert!(chunks.next().is_none());
+}
+
+#[test]
+fn test_into_array_chunks_remainder() {
+    let drops = Cell::new(0);
+    declare_stackptr!{
+        let slice: StackPtr<[Tagged]> = StackPtr::new([
+            Tagged { tag: 1, _counter: DropCounter(&drops) },
+            Tagged { tag: 2, _counter: DropCounter(&drops) },
+            Tagged { tag: 3, _counter: DropCounter(&drops) },
+            Tagged { tag: 4, _counter: DropCounter(&drops) },
+            Tagged { tag: 5, _counter: DropCounter(&drops) },
+        ]);
+    }
+
+    let mut chunks = StackPtr::into_array_chunks::<2>(slice);
+    let first = chunks.next().unwrap();
+    let second = chunks.next().unwrap();
+    assert_eq!([first[0].tag, first[1].tag, second[0].tag, second[1].tag], [1, 2, 3, 4]);
+    assert!(chunks.next().is_none());
+    assert_eq!(drops.get(), 0);
+
+    // the fifth element doesn't make up a full chunk and is dropped by the iterator
+    drop(chunks);
+    assert_eq!(drops.get(), 1);
+    drop((first, second));
+    assert_eq!(drops.get(), 5);
+}
+
+#[test]
+#[should_panic(expected = "chunk size must be non-zero")]
+fn test_into_array_chunks_zero() {
+    declare_stackptr!{
+        let slice: StackPtr<[i32]> = StackPtr::new([1,2,3]);
+    }
+
+    StackPtr::into_array_chunks::<0>(slice);
+}
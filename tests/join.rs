@@ -0,0 +1,56 @@
+#[macro_use]
+extern crate stack_ptr;
+
+use std::cell::Cell;
+use std::future::{self, Future};
+use std::pin::Pin;
+use std::task::{Context, Poll, Waker};
+
+/// Completes with `value` after returning `Pending` a fixed number of times.
+struct CountDown<'a> {
+    remaining: u32,
+    value: i32,
+    polls: &'a Cell<u32>,
+}
+
+impl<'a> Future for CountDown<'a> {
+    type Output = i32;
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context) -> Poll<i32> {
+        self.polls.set(self.polls.get() + 1);
+        if self.remaining == 0 {
+            Poll::Ready(self.value)
+        } else {
+            self.remaining -= 1;
+            cx.waker().wake_by_ref();
+            Poll::Pending
+        }
+    }
+}
+
+#[test]
+fn test_join_stack() {
+    let polls = Cell::new(0);
+    join_stack!(let mut joined =
+        CountDown { remaining: 2, value: 1, polls: &polls },
+        future::ready(2),
+        CountDown { remaining: 0, value: 3, polls: &polls },
+    );
+
+    let mut cx = Context::from_waker(Waker::noop());
+    assert_eq!(Pin::new(&mut joined).poll(&mut cx), Poll::Pending);
+    assert_eq!(Pin::new(&mut joined).poll(&mut cx), Poll::Pending);
+    assert_eq!(Pin::new(&mut joined).poll(&mut cx), Poll::Ready([1, 2, 3]));
+    // futures that have completed aren't polled again
+    assert_eq!(polls.get(), 4);
+}
+
+#[test]
+#[should_panic(expected = "StackJoin polled after completion")]
+fn test_join_stack_poll_after_completion() {
+    join_stack!(let mut joined = future::ready(()));
+
+    let mut cx = Context::from_waker(Waker::noop());
+    assert_eq!(Pin::new(&mut joined).poll(&mut cx), Poll::Ready([()]));
+    let _ = Pin::new(&mut joined).poll(&mut cx);
+}
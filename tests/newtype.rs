@@ -0,0 +1,85 @@
+#[macro_use]
+extern crate stack_ptr;
+
+use std::fmt::{self, Debug, Display, Formatter};
+
+stack_newtype! {
+    /// A label rendered with `Display`.
+    struct Label(dyn Display);
+
+    impl Display {
+        fn fmt(&self, f: &mut Formatter) -> fmt::Result;
+    }
+}
+
+stack_newtype! {
+    struct Dump(dyn Debug);
+
+    impl Debug {
+        fn fmt(&self, f: &mut Formatter) -> fmt::Result;
+    }
+}
+
+trait Counter {
+    fn get(&self) -> u32;
+    fn add(&mut self, amount: u32) -> u32;
+}
+
+struct Simple(u32);
+
+impl Counter for Simple {
+    fn get(&self) -> u32 {
+        self.0
+    }
+
+    fn add(&mut self, amount: u32) -> u32 {
+        self.0 += amount;
+        self.0
+    }
+}
+
+stack_newtype! {
+    struct Tally(dyn Counter + Send);
+
+    impl Counter {
+        fn get(&self) -> u32;
+        fn add(&mut self, amount: u32) -> u32;
+    }
+}
+
+#[test]
+fn test_display_newtype() {
+    declare_stackptr!{
+        let text: StackPtr<dyn Display> = StackPtr::new("hello");
+    }
+
+    let label = Label::new(text);
+    assert_eq!(label.to_string(), "hello");
+    assert_eq!(format!("[{:>7}]", label), "[  hello]");
+
+    let inner = label.into_inner();
+    assert_eq!(inner.to_string(), "hello");
+}
+
+#[test]
+fn test_method_forwarding() {
+    declare_stackptr!{
+        let counter: StackPtr<dyn Counter + Send> = StackPtr::new(Simple(1));
+    }
+
+    let mut tally = Tally::new(counter);
+    assert_eq!(tally.add(2), 3);
+    assert_eq!(Counter::get(&tally), 3);
+    assert_eq!(tally.get(), 3);
+}
+
+#[test]
+fn test_debug_forwarding() {
+    declare_stackptr!{
+        let value: StackPtr<dyn Debug> = StackPtr::new(Some("hi"));
+    }
+
+    let dump = Dump::new(value);
+    assert_eq!(format!("{:?}", dump), "Some(\"hi\")");
+    assert_eq!(format!("{:#?}", dump), "Some(\n    \"hi\",\n)");
+}
@@ -0,0 +1,128 @@
+#[macro_use]
+extern crate stack_ptr;
+
+use std::future::Future;
+use std::marker::PhantomPinned;
+use std::pin::Pin;
+use std::task::{Context, Poll, Waker};
+
+/// A future that records its own address on the first poll and checks on every later poll that it hasn't moved.
+struct AddressCheck {
+    address: Option<usize>,
+    polls: u32,
+    _pinned: PhantomPinned,
+}
+
+impl AddressCheck {
+    fn new() -> AddressCheck {
+        AddressCheck { address: None, polls: 0, _pinned: PhantomPinned }
+    }
+}
+
+impl Future for AddressCheck {
+    type Output = u32;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context) -> Poll<u32> {
+        let address = &*self as *const AddressCheck as usize;
+        let this = unsafe { self.get_unchecked_mut() };
+        assert_eq!(*this.address.get_or_insert(address), address);
+        this.polls += 1;
+        if this.polls < 3 {
+            cx.waker().wake_by_ref();
+            Poll::Pending
+        } else {
+            Poll::Ready(this.polls)
+        }
+    }
+}
+
+#[test]
+fn test_pin_stackptr() {
+    pin_stackptr!{
+        let fut = StackPtr::new(AddressCheck::new());
+    }
+
+    let mut cx = Context::from_waker(Waker::noop());
+    assert_eq!(fut.as_mut().poll(&mut cx), Poll::Pending);
+    assert_eq!(fut.as_mut().poll(&mut cx), Poll::Pending);
+    assert_eq!(fut.as_mut().poll(&mut cx), Poll::Ready(3));
+    assert!(fut.address.is_some());
+}
+
+#[test]
+fn test_pin_stackptr_dyn() {
+    pin_stackptr!{
+        let fut: StackPtr<dyn Future<Output = u32>> = StackPtr::new(AddressCheck::new());
+    }
+
+    let mut cx = Context::from_waker(Waker::noop());
+    let mut result = fut.as_mut().poll(&mut cx);
+    while result.is_pending() {
+        result = fut.as_mut().poll(&mut cx);
+    }
+    assert_eq!(result, Poll::Ready(3));
+}
+
+#[test]
+fn test_as_pin_mut_slice() {
+    use stack_ptr::StackPtr;
+
+    declare_stackptr!{
+        let futures: StackPtr<[AddressCheck]> = StackPtr::new([AddressCheck::new(), AddressCheck::new()]);
+    }
+    let mut futures = std::pin::pin!(futures);
+
+    let mut cx = Context::from_waker(Waker::noop());
+    let mut results = [Poll::Pending, Poll::Pending];
+    while results.iter().any(|r| r.is_pending()) {
+        // `futures` is pinned in this frame, so it is dropped before the storage goes away
+        let mut slice = unsafe { StackPtr::as_pin_mut_slice(futures.as_mut()) };
+        for (i, result) in results.iter_mut().enumerate() {
+            if result.is_pending() {
+                let fut = unsafe { slice.as_mut().map_unchecked_mut(|s| &mut s[i]) };
+                *result = fut.poll(&mut cx);
+            }
+        }
+    }
+    assert_eq!(results, [Poll::Ready(3), Poll::Ready(3)]);
+    assert_eq!(futures.len(), 2);
+}
+
+#[test]
+fn test_pin_from_unpin() {
+    use stack_ptr::StackPtr;
+
+    declare_stackptr!{
+        let fut = StackPtr::new(std::future::ready(5));
+    }
+    let mut fut: Pin<StackPtr<_>> = fut.into();
+
+    let mut cx = Context::from_waker(Waker::noop());
+    assert_eq!(fut.as_mut().poll(&mut cx), Poll::Ready(5));
+
+    declare_stackptr!{
+        let count = StackPtr::new(1);
+    }
+    let mut count = Pin::from(count);
+    *count.as_mut().get_mut() += 1;
+    assert_eq!(*count, 2);
+    assert_eq!(*Pin::into_inner(count), 2);
+}
+
+#[test]
+fn test_into_pin_unchecked() {
+    use stack_ptr::StackPtr;
+
+    declare_stackptr!{
+        let fut = StackPtr::new(AddressCheck::new());
+    }
+    // the pinned StackPtr is dropped at the end of this function, before its storage
+    let mut fut = unsafe { StackPtr::into_pin_unchecked(fut) };
+
+    let mut cx = Context::from_waker(Waker::noop());
+    let mut result = fut.as_mut().poll(&mut cx);
+    while result.is_pending() {
+        result = fut.as_mut().poll(&mut cx);
+    }
+    assert_eq!(result, Poll::Ready(3));
+}
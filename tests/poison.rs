@@ -0,0 +1,32 @@
+#![cfg(all(feature = "debug-poison", debug_assertions))]
+
+extern crate stack_ptr;
+
+use std::mem::MaybeUninit;
+
+use stack_ptr::StackPtr;
+
+#[test]
+fn test_poison_after_drop() {
+    let mut slot = MaybeUninit::uninit();
+    let sp = StackPtr::from_value(&mut slot, [1u32, 2, 3, 4]);
+
+    let (ptr, lifetime) = StackPtr::into_raw_parts(sp);
+    let stale = ptr as *const u32;
+    let sp = unsafe { StackPtr::from_raw_parts(ptr, lifetime) };
+    assert_eq!(unsafe { *stale.add(2) }, 3);
+    drop(sp);
+
+    assert_eq!(unsafe { *stale.add(2) }, 0xA5A5A5A5);
+}
+
+#[test]
+fn test_poison_slice() {
+    let mut slot = MaybeUninit::uninit();
+    let sp = StackPtr::from_value(&mut slot, [7u8; 6]);
+    let (ptr, lifetime) = StackPtr::into_raw_parts(sp);
+    let sp: StackPtr<[u8]> = unsafe { StackPtr::from_raw_parts(ptr as *mut [u8], lifetime) };
+    drop(sp);
+
+    assert_eq!(unsafe { slot.assume_init() }, [0xA5; 6]);
+}
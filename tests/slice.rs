@@ -0,0 +1,952 @@
+#[macro_use]
+extern crate stack_ptr;
+
+mod common;
+
+use std::cell::Cell;
+
+use common::{Counted, DropCounter};
+use stack_ptr::StackPtr;
+
+#[repr(C, align(4))]
+struct Aligned([u8; 4]);
+
+#[test]
+fn test_from_byte_slice() {
+    let mut storage = Aligned(0x01020304u32.to_ne_bytes());
+    let bytes: StackPtr<[u8]> = unsafe { StackPtr::from_mut(&mut storage.0[..]) };
+    let value: StackPtr<u32> = unsafe { StackPtr::from_byte_slice(bytes) };
+
+    assert_eq!(*value, 0x01020304);
+}
+
+#[test]
+fn test_as_bytes_array() {
+    #[repr(C)]
+    #[derive(Clone, Copy)]
+    struct Header {
+        kind: u16,
+        flags: u16,
+        len: u32,
+    }
+
+    declare_stackptr!{
+        let header = StackPtr::new(Header { kind: 1, flags: 0x0203, len: 0x04050607 });
+    }
+
+    let bytes: StackPtr<[u8; 8]> = unsafe { StackPtr::as_bytes_array(header) };
+    let mut expected = Vec::new();
+    expected.extend_from_slice(&1u16.to_ne_bytes());
+    expected.extend_from_slice(&0x0203u16.to_ne_bytes());
+    expected.extend_from_slice(&0x04050607u32.to_ne_bytes());
+    assert_eq!(&bytes[..], &expected[..]);
+}
+
+#[test]
+fn test_as_io_slice() {
+    use std::io::{Read, Write};
+
+    declare_stackptr!{
+        let header: StackPtr<[u8]> = StackPtr::new(*b"len=5;");
+    }
+    declare_stackptr!{
+        let body: StackPtr<[u8]> = StackPtr::new(*b"hello");
+    }
+
+    let slices = [header.as_io_slice(), body.as_io_slice()];
+    assert_eq!((slices[0].len(), slices[1].len()), (6, 5));
+    let mut out = Vec::new();
+    let written = out.write_vectored(&slices).unwrap();
+    assert_eq!(written, 11);
+    assert_eq!(out, b"len=5;hello");
+
+    declare_stackptr!{
+        let mut first: StackPtr<[u8]> = StackPtr::new([0; 4]);
+    }
+    declare_stackptr!{
+        let mut second: StackPtr<[u8]> = StackPtr::new([0; 4]);
+    }
+    let mut input: &[u8] = b"abcdefg";
+    let read = input.read_vectored(&mut [first.as_io_slice_mut(), second.as_io_slice_mut()]).unwrap();
+    assert_eq!(read, 7);
+    assert_eq!(first, b"abcd");
+    assert_eq!(second, [b'e', b'f', b'g', 0]);
+}
+
+#[test]
+fn test_stack_concat() {
+    declare_stackptr!{
+        let a: StackPtr<[i32]> = StackPtr::new([1,2,3]);
+    }
+    declare_stackptr!{
+        let b: StackPtr<[i32]> = StackPtr::new([4,5]);
+    }
+
+    stack_concat!(let joined = 8, a, b);
+    assert_eq!(&*joined, &[1,2,3,4,5]);
+}
+
+#[test]
+fn test_stack_concat_empty() {
+    declare_stackptr!{
+        let a: StackPtr<[i32]> = StackPtr::new([]);
+    }
+    declare_stackptr!{
+        let b: StackPtr<[i32]> = StackPtr::new([1]);
+    }
+
+    stack_concat!(let joined = 1, a, b);
+    assert_eq!(&*joined, &[1]);
+}
+
+#[test]
+fn test_stack_concat_drops() {
+    let drops = Cell::new(0);
+    {
+        declare_stackptr!{
+            let a: StackPtr<[DropCounter]> = StackPtr::new([DropCounter(&drops), DropCounter(&drops)]);
+        }
+        declare_stackptr!{
+            let b: StackPtr<[DropCounter]> = StackPtr::new([DropCounter(&drops)]);
+        }
+
+        stack_concat!(let joined = 4, a, b);
+        assert_eq!(joined.len(), 3);
+        assert_eq!(drops.get(), 0);
+    }
+    assert_eq!(drops.get(), 3);
+}
+
+#[test]
+#[should_panic]
+fn test_stack_concat_overflow() {
+    declare_stackptr!{
+        let a: StackPtr<[i32]> = StackPtr::new([1,2]);
+    }
+    declare_stackptr!{
+        let b: StackPtr<[i32]> = StackPtr::new([3,4]);
+    }
+
+    stack_concat!(let _joined = 3, a, b);
+}
+
+#[test]
+fn test_reverse() {
+    declare_stackptr!{
+        let mut slice: StackPtr<[i32]> = StackPtr::new([1,2,3,4]);
+    }
+
+    slice.reverse();
+    assert_eq!(&*slice, &[4,3,2,1]);
+}
+
+#[test]
+fn test_windows() {
+    declare_stackptr!{
+        let slice: StackPtr<[i32]> = StackPtr::new([1,3,6,10]);
+    }
+
+    let diffs: Vec<i32> = slice.windows(2).map(|w| w[1] - w[0]).collect();
+    assert_eq!(diffs, [2,3,4]);
+}
+
+#[test]
+fn test_chunks() {
+    declare_stackptr!{
+        let slice: StackPtr<[i32]> = StackPtr::new([1,2,3,4,5]);
+    }
+
+    let sums: Vec<i32> = slice.chunks(2).map(|c| c.iter().sum()).collect();
+    assert_eq!(sums, [3,7,5]);
+    assert_eq!(slice.len(), 5);
+}
+
+#[test]
+fn test_chunks_exact() {
+    declare_stackptr!{
+        let slice: StackPtr<[i32]> = StackPtr::new([1,2,3,4,5]);
+    }
+
+    let mut chunks = slice.chunks_exact(2);
+    assert_eq!(chunks.next(), Some(&[1,2][..]));
+    assert_eq!(chunks.next(), Some(&[3,4][..]));
+    assert_eq!(chunks.next(), None);
+    assert_eq!(chunks.remainder(), &[5]);
+}
+
+#[test]
+fn test_rchunks() {
+    declare_stackptr!{
+        let slice: StackPtr<[i32]> = StackPtr::new([1,2,3,4,5]);
+    }
+
+    let chunks: Vec<&[i32]> = slice.rchunks(2).collect();
+    assert_eq!(chunks, [&[4,5][..], &[2,3], &[1]]);
+}
+
+#[test]
+fn test_split_at_mut_borrow() {
+    declare_stackptr!{
+        let mut slice: StackPtr<[i32]> = StackPtr::new([1,2,3,4,5]);
+    }
+
+    {
+        let (left, right) = slice.split_at_mut_borrow(2);
+        left.reverse();
+        for x in right.iter_mut() {
+            *x *= 10;
+        }
+        left[0] += right[0];
+    }
+    assert_eq!(slice, [32, 1, 30, 40, 50]);
+
+    let (empty, all) = slice.split_at_mut_borrow(0);
+    assert!(empty.is_empty());
+    assert_eq!(all.len(), 5);
+}
+
+#[test]
+fn test_from_array_ref() {
+    use std::mem::ManuallyDrop;
+
+    let drops = Cell::new(0);
+    {
+        let mut arr = ManuallyDrop::new([DropCounter(&drops), DropCounter(&drops), DropCounter(&drops)]);
+        let slice = unsafe { StackPtr::from_array_ref(&mut arr) };
+        assert_eq!(slice.len(), 3);
+    }
+    assert_eq!(drops.get(), 3);
+}
+
+#[test]
+fn test_rotate() {
+    declare_stackptr!{
+        let mut slice: StackPtr<[i32]> = StackPtr::new([1,2,3,4,5]);
+    }
+
+    slice.rotate_left(2);
+    assert_eq!(&*slice, &[3,4,5,1,2]);
+    slice.rotate_right(3);
+    assert_eq!(&*slice, &[5,1,2,3,4]);
+}
+
+#[test]
+fn test_fill() {
+    declare_stackptr!{
+        let mut slice: StackPtr<[i32]> = StackPtr::new([1,2,3]);
+    }
+
+    slice.fill(7);
+    assert_eq!(&*slice, &[7,7,7]);
+}
+
+#[test]
+fn test_fill_with() {
+    declare_stackptr!{
+        let mut slice: StackPtr<[i32]> = StackPtr::new([0; 4]);
+    }
+
+    let mut counter = 0;
+    slice.fill_with(|| {
+        counter += 1;
+        counter
+    });
+    assert_eq!(&*slice, &[1,2,3,4]);
+}
+
+#[test]
+fn test_as_array() {
+    declare_stackptr!{
+        let mut slice: StackPtr<[i32]> = StackPtr::new([1,2,3]);
+    }
+
+    assert_eq!(slice.as_array::<3>(), Some(&[1,2,3]));
+    assert_eq!(slice.as_array::<2>(), None);
+
+    slice.as_array_mut::<3>().unwrap()[0] = 10;
+    assert!(slice.as_array_mut::<4>().is_none());
+    assert_eq!(&*slice, &[10,2,3]);
+}
+
+#[test]
+fn test_dedup() {
+    let drops = Cell::new(0);
+    declare_stackptr!{
+        let slice: StackPtr<[Counted]> = StackPtr::new([
+            Counted::new(1, &drops),
+            Counted::new(1, &drops),
+            Counted::new(2, &drops),
+            Counted::new(3, &drops),
+            Counted::new(3, &drops),
+            Counted::new(3, &drops),
+            Counted::new(1, &drops),
+        ]);
+    }
+
+    let deduped = StackPtr::dedup(slice);
+    assert_eq!(drops.get(), 3);
+    assert_eq!(deduped.iter().map(|c| c.value).collect::<Vec<_>>(), [1,2,3,1]);
+
+    drop(deduped);
+    assert_eq!(drops.get(), 7);
+}
+
+#[test]
+fn test_dedup_empty() {
+    declare_stackptr!{
+        let slice: StackPtr<[i32]> = StackPtr::new([]);
+    }
+
+    assert!(StackPtr::dedup(slice).is_empty());
+}
+
+#[test]
+fn test_into_drain_filter() {
+    let drops = Cell::new(0);
+    declare_stackptr!{
+        let slice: StackPtr<[Counted]> = StackPtr::new([
+            Counted::new(1, &drops),
+            Counted::new(2, &drops),
+            Counted::new(3, &drops),
+            Counted::new(4, &drops),
+            Counted::new(5, &drops),
+        ]);
+    }
+
+    let (removed, kept) = StackPtr::into_drain_filter(slice, |c| {
+        c.value *= 10;
+        c.value % 20 == 0
+    });
+    assert_eq!(removed.iter().map(|c| c.value).collect::<Vec<_>>(), [20, 40]);
+    assert_eq!(kept.iter().map(|c| c.value).collect::<Vec<_>>(), [10, 30, 50]);
+    assert_eq!(drops.get(), 0);
+
+    drop(removed);
+    assert_eq!(drops.get(), 2);
+    drop(kept);
+    assert_eq!(drops.get(), 5);
+}
+
+#[test]
+fn test_into_drain_filter_panic() {
+    let drops = Cell::new(0);
+    let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+        declare_stackptr!{
+            let slice: StackPtr<[Counted]> = StackPtr::new([
+                Counted::new(1, &drops),
+                Counted::new(2, &drops),
+                Counted::new(3, &drops),
+                Counted::new(4, &drops),
+            ]);
+        }
+
+        StackPtr::into_drain_filter(slice, |c| {
+            assert!(c.value != 3, "predicate failed");
+            c.value == 2
+        });
+    }));
+    assert!(result.is_err());
+    assert_eq!(drops.get(), 4);
+}
+
+#[test]
+fn test_iter_mut_enumerate() {
+    declare_stackptr!{
+        let mut slice: StackPtr<[usize]> = StackPtr::new([10,10,10]);
+    }
+
+    for (i, elem) in slice.iter_mut_enumerate() {
+        *elem += i;
+    }
+    assert_eq!(&*slice, &[10,11,12]);
+}
+
+#[test]
+fn test_get_unchecked() {
+    declare_stackptr!{
+        let mut slice: StackPtr<[i32]> = StackPtr::new([1,2,3,4]);
+    }
+
+    let mut sum = 0;
+    for i in 0..slice.len() {
+        sum += unsafe { *slice.get_unchecked(i) };
+    }
+    assert_eq!(sum, 10);
+
+    unsafe {
+        *slice.get_unchecked_mut(3) = 40;
+    }
+    assert_eq!(&*slice, &[1,2,3,40]);
+    assert_eq!(unsafe { slice.get_unchecked(0..2) }, &[1,2]);
+}
+
+#[test]
+fn test_truncate() {
+    let drops = Cell::new(0);
+    declare_stackptr!{
+        let slice: StackPtr<[Counted]> = StackPtr::new([
+            Counted::new(1, &drops),
+            Counted::new(2, &drops),
+            Counted::new(3, &drops),
+            Counted::new(4, &drops),
+        ]);
+    }
+
+    let slice = StackPtr::truncate(slice, 10);
+    assert_eq!(slice.len(), 4);
+    assert_eq!(drops.get(), 0);
+
+    let slice = StackPtr::truncate(slice, 2);
+    assert_eq!(slice.iter().map(|c| c.value).collect::<Vec<_>>(), vec![1, 2]);
+    assert_eq!(drops.get(), 2);
+
+    let slice = StackPtr::truncate(slice, 0);
+    assert!(slice.is_empty());
+    assert_eq!(drops.get(), 4);
+
+    drop(slice);
+    assert_eq!(drops.get(), 4);
+}
+
+#[test]
+fn test_stack_slice() {
+    let drops = Cell::new(0);
+    stack_slice!(let slice = Counted::new(1, &drops), Counted::new(2, &drops), Counted::new(3, &drops));
+    assert_eq!(slice.len(), 3);
+
+    let values: Vec<i32> = slice.into_iter().map(|c| c.value).collect();
+    assert_eq!(values, vec![1, 2, 3]);
+    assert_eq!(drops.get(), 3);
+
+    stack_slice!(let mut numbers = 3, 1, 2,);
+    numbers.sort();
+    assert_eq!(numbers, [1, 2, 3]);
+}
+
+#[test]
+fn test_split_once() {
+    let drops = Cell::new(0);
+    declare_stackptr!{
+        let slice: StackPtr<[Counted]> = StackPtr::new([
+            Counted::new(1, &drops),
+            Counted::new(0, &drops),
+            Counted::new(2, &drops),
+            Counted::new(0, &drops),
+            Counted::new(3, &drops),
+        ]);
+    }
+
+    let (head, tail) = StackPtr::split_once(slice, |c| c.value == 0);
+    assert_eq!(drops.get(), 1);
+    assert_eq!(head.iter().map(|c| c.value).collect::<Vec<_>>(), vec![1]);
+    let tail = tail.unwrap();
+    assert_eq!(tail.iter().map(|c| c.value).collect::<Vec<_>>(), vec![2, 0, 3]);
+
+    drop(head);
+    drop(tail);
+    assert_eq!(drops.get(), 5);
+}
+
+#[test]
+fn test_split_once_absent() {
+    let drops = Cell::new(0);
+    declare_stackptr!{
+        let slice: StackPtr<[Counted]> = StackPtr::new([
+            Counted::new(1, &drops),
+            Counted::new(2, &drops),
+        ]);
+    }
+
+    let (head, tail) = StackPtr::split_once(slice, |c| c.value == 0);
+    assert!(tail.is_none());
+    assert_eq!(head.len(), 2);
+    assert_eq!(drops.get(), 0);
+
+    drop(head);
+    assert_eq!(drops.get(), 2);
+}
+
+#[test]
+fn test_stack_collect_exact() {
+    let drops = Cell::new(0);
+    stack_collect_exact!(let array = 3, (1..=3).map(|i| Counted::new(i, &drops)));
+    assert_eq!(array.iter().map(|c| c.value).collect::<Vec<_>>(), vec![1, 2, 3]);
+
+    let slice = coerce_stackptr!(array, [Counted]);
+    assert_eq!(slice.len(), 3);
+    drop(slice);
+    assert_eq!(drops.get(), 3);
+}
+
+#[test]
+fn test_stack_collect_exact_too_few() {
+    let drops = Cell::new(0);
+    let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+        stack_collect_exact!(let array = 3, (1..=2).map(|i| Counted::new(i, &drops)));
+        drop(array);
+    }));
+    let message = *result.unwrap_err().downcast::<String>().unwrap();
+    assert_eq!(message, "iterator yielded 2 items, expected exactly 3");
+    assert_eq!(drops.get(), 2);
+}
+
+#[test]
+fn test_stack_collect_exact_too_many() {
+    let drops = Cell::new(0);
+    let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+        stack_collect_exact!(let array = 2, (1..=5).map(|i| Counted::new(i, &drops)));
+        drop(array);
+    }));
+    let message = *result.unwrap_err().downcast::<String>().unwrap();
+    assert_eq!(message, "iterator yielded more than 2 items");
+    // the two collected items and the extra one; the rest are never produced
+    assert_eq!(drops.get(), 3);
+}
+
+#[test]
+fn test_is_sorted() {
+    declare_stackptr!{
+        let mut slice: StackPtr<[i32]> = StackPtr::new([3,1,2]);
+    }
+
+    assert!(!slice.is_sorted());
+    slice.sort();
+    assert!(slice.is_sorted());
+}
+
+#[test]
+fn test_stack_concat_iter() {
+    let drops = Cell::new(0);
+    {
+        stack_slice!(let a = Counted::new(1, &drops), Counted::new(2, &drops));
+        stack_slice!(let b = Counted::new(3, &drops));
+        declare_stackptr!{
+            let empty: StackPtr<[Counted]> = StackPtr::new([]);
+        }
+        stack_slice!(let c = Counted::new(4, &drops), Counted::new(5, &drops));
+
+        stack_concat_iter!(let joined = 8, vec![a, empty, b, c]);
+        assert_eq!(joined.iter().map(|c| c.value).collect::<Vec<_>>(), vec![1, 2, 3, 4, 5]);
+        assert_eq!(drops.get(), 0);
+    }
+    assert_eq!(drops.get(), 5);
+}
+
+#[test]
+fn test_stack_concat_iter_edge_cases() {
+    stack_concat_iter!(let none = 2, Vec::<StackPtr<[i32]>>::new());
+    assert!(none.is_empty());
+
+    stack_slice!(let only = 1, 2, 3);
+    stack_concat_iter!(let single = 3, Some(only));
+    assert_eq!(single, [1, 2, 3]);
+}
+
+#[test]
+fn test_stack_concat_iter_overflow() {
+    let drops = Cell::new(0);
+    let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+        stack_slice!(let a = Counted::new(1, &drops), Counted::new(2, &drops));
+        stack_slice!(let b = Counted::new(3, &drops), Counted::new(4, &drops));
+        stack_slice!(let c = Counted::new(5, &drops));
+        stack_concat_iter!(let joined = 3, vec![a, b, c]);
+        drop(joined);
+    }));
+    assert!(result.is_err());
+    assert_eq!(drops.get(), 5);
+}
+
+#[test]
+fn test_stack_from_fn() {
+    stack_from_fn!(let mut labels = 3, |i| format!("item {}", i));
+    assert_eq!(labels, [String::from("item 0"), String::from("item 1"), String::from("item 2")]);
+    labels[1].push('!');
+    assert_eq!(labels[1], "item 1!");
+}
+
+#[test]
+fn test_stack_from_fn_panic() {
+    let drops = Cell::new(0);
+    let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+        stack_from_fn!(let counted = 5, |i| {
+            assert!(i != 3, "failed at index {}", i);
+            Counted::new(i as i32, &drops)
+        });
+        drop(counted);
+    }));
+    assert!(result.is_err());
+    // only the three elements built before the panic existed, and each was dropped once
+    assert_eq!(drops.get(), 3);
+}
+
+#[test]
+fn test_stack_repeat() {
+    use std::rc::Rc;
+
+    let value = Rc::new(7);
+    {
+        let n = 3;
+        stack_repeat!(let copies = 5, value.clone(), n);
+        assert_eq!(copies.len(), 3);
+        assert!(copies.iter().all(|c| **c == 7));
+        assert_eq!(Rc::strong_count(&value), 4);
+    }
+    assert_eq!(Rc::strong_count(&value), 1);
+}
+
+#[test]
+fn test_stack_repeat_edge_cases() {
+    use std::rc::Rc;
+
+    let value = Rc::new(());
+    {
+        stack_repeat!(let empty = 4, value.clone(), 0);
+        assert!(empty.is_empty());
+        // the value passed in is dropped rather than stored
+        assert_eq!(Rc::strong_count(&value), 1);
+
+        stack_repeat!(let full = 4, value.clone(), 4);
+        assert_eq!(full.len(), 4);
+        assert_eq!(Rc::strong_count(&value), 5);
+    }
+    assert_eq!(Rc::strong_count(&value), 1);
+}
+
+#[test]
+#[should_panic(expected = "repeat count 5 exceeds buffer capacity 4")]
+fn test_stack_repeat_overflow() {
+    stack_repeat!(let too_many = 4, 0, 5);
+    drop(too_many);
+}
+
+#[test]
+fn test_narrow() {
+    let drops = Cell::new(0);
+    declare_stackptr!{
+        let slice: StackPtr<[Counted]> = StackPtr::new([
+            Counted::new(1, &drops),
+            Counted::new(2, &drops),
+            Counted::new(3, &drops),
+            Counted::new(4, &drops),
+            Counted::new(5, &drops),
+        ]);
+    }
+
+    let slice = StackPtr::narrow(slice, 1..4);
+    assert_eq!(slice.iter().map(|c| c.value).collect::<Vec<_>>(), vec![2, 3, 4]);
+    assert_eq!(drops.get(), 2);
+
+    let slice = StackPtr::narrow(slice, 0..3);
+    assert_eq!(slice.len(), 3);
+    assert_eq!(drops.get(), 2);
+
+    let slice = StackPtr::narrow(slice, 2..2);
+    assert!(slice.is_empty());
+    assert_eq!(drops.get(), 5);
+}
+
+#[test]
+#[should_panic(expected = "out of bounds")]
+fn test_narrow_out_of_bounds() {
+    declare_stackptr!{
+        let slice: StackPtr<[i32]> = StackPtr::new([1,2,3]);
+    }
+
+    StackPtr::narrow(slice, 1..4);
+}
+
+#[test]
+fn test_into_single() {
+    let drops = Cell::new(0);
+    stack_slice!(let one = Counted::new(1, &drops));
+    let single = StackPtr::into_single(one).unwrap_or_else(|_| panic!("expected a single element"));
+    assert_eq!(single.value, 1);
+    drop(single);
+    assert_eq!(drops.get(), 1);
+
+    stack_slice!(let two = Counted::new(2, &drops), Counted::new(3, &drops));
+    let two = StackPtr::into_single(two).err().unwrap();
+    assert_eq!(two.len(), 2);
+    assert_eq!(drops.get(), 1);
+    drop(two);
+    assert_eq!(drops.get(), 3);
+}
+
+#[test]
+fn test_into_slice_of_one() {
+    declare_stackptr!{
+        let name = StackPtr::new(String::from("only"));
+    }
+
+    let slice = StackPtr::into_slice_of_one(name);
+    assert_eq!(slice.len(), 1);
+    let names: Vec<String> = slice.into_iter().collect();
+    assert_eq!(names, vec![String::from("only")]);
+
+    let drops = Cell::new(0);
+    declare_stackptr!{
+        let counted = StackPtr::new(Counted::new(4, &drops));
+    }
+    let slice = StackPtr::into_slice_of_one(counted);
+    assert_eq!(slice[0].value, 4);
+    drop(slice);
+    assert_eq!(drops.get(), 1);
+}
+
+#[test]
+fn test_swap_remove() {
+    let drops = Cell::new(0);
+    stack_slice!(let slice = Counted::new(1, &drops), Counted::new(2, &drops), Counted::new(3, &drops), Counted::new(4, &drops));
+
+    let (removed, slice) = StackPtr::swap_remove(slice, 1);
+    assert_eq!(removed.value, 2);
+    assert_eq!(slice.iter().map(|c| c.value).collect::<Vec<_>>(), vec![1, 4, 3]);
+    assert_eq!(drops.get(), 0);
+    drop(removed);
+    assert_eq!(drops.get(), 1);
+
+    let (removed, slice) = StackPtr::swap_remove(slice, 2);
+    assert_eq!(removed.value, 3);
+    assert_eq!(slice.iter().map(|c| c.value).collect::<Vec<_>>(), vec![1, 4]);
+
+    drop(slice);
+    assert_eq!(drops.get(), 3);
+    drop(removed);
+    assert_eq!(drops.get(), 4);
+}
+
+#[test]
+#[should_panic(expected = "out of bounds")]
+fn test_swap_remove_out_of_bounds() {
+    stack_slice!(let slice = 1, 2);
+    StackPtr::swap_remove(slice, 2);
+}
+
+#[test]
+fn test_stack_interleave() {
+    let drops = Cell::new(0);
+    {
+        stack_slice!(let a = Counted::new(1, &drops), Counted::new(3, &drops), Counted::new(5, &drops));
+        stack_slice!(let b = Counted::new(2, &drops), Counted::new(4, &drops), Counted::new(6, &drops));
+
+        stack_interleave!(let merged = 6, a, b);
+        assert_eq!(merged.iter().map(|c| c.value).collect::<Vec<_>>(), vec![1, 2, 3, 4, 5, 6]);
+        assert_eq!(drops.get(), 0);
+    }
+    assert_eq!(drops.get(), 6);
+}
+
+#[test]
+fn test_stack_interleave_unequal() {
+    let drops = Cell::new(0);
+    let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+        stack_slice!(let a = Counted::new(1, &drops), Counted::new(3, &drops));
+        stack_slice!(let b = Counted::new(2, &drops));
+        stack_interleave!(let merged = 4, a, b);
+        drop(merged);
+    }));
+    assert!(result.is_err());
+    assert_eq!(drops.get(), 3);
+}
+
+#[test]
+fn test_transmute_slice() {
+    declare_stackptr!{
+        let words: StackPtr<[u32]> = StackPtr::new([0x01020304, 0x05060708]);
+    }
+
+    let bytes = unsafe { StackPtr::transmute_slice::<u8>(words) };
+    assert_eq!(bytes.len(), 8);
+    let expected: Vec<u8> = [0x01020304u32, 0x05060708].iter().flat_map(|w| w.to_ne_bytes()).collect();
+    assert_eq!(bytes, &expected[..]);
+
+    let words = unsafe { StackPtr::transmute_slice::<u32>(bytes) };
+    assert_eq!(words, [0x01020304, 0x05060708]);
+}
+
+#[cfg(debug_assertions)]
+#[test]
+#[should_panic(expected = "not a multiple")]
+fn test_transmute_slice_bad_length() {
+    declare_stackptr!{
+        let words: StackPtr<[u16]> = StackPtr::new([1, 2, 3]);
+    }
+
+    unsafe {
+        StackPtr::transmute_slice::<u32>(words);
+    }
+}
+
+#[test]
+fn test_narrow_to_payload() {
+    declare_stackptr!{
+        let buf: StackPtr<[u8]> = StackPtr::new([3, b'a', b'b', b'c', 0, 0, 0, 0]);
+    }
+
+    let declared = buf[0] as usize;
+    let (_, payload) = StackPtr::into_mut(buf).split_at_mut(1);
+    let payload: StackPtr<[u8]> = unsafe { StackPtr::from_mut(payload) };
+    let payload = StackPtr::narrow_to_payload(payload, declared).unwrap();
+    assert_eq!(payload, b"abc");
+}
+
+#[test]
+fn test_narrow_to_payload_too_long() {
+    declare_stackptr!{
+        let buf: StackPtr<[u8]> = StackPtr::new([1, 2, 3]);
+    }
+
+    let buf = StackPtr::narrow_to_payload(buf, 4).unwrap_err();
+    assert_eq!(buf, [1, 2, 3]);
+    let buf = StackPtr::narrow_to_payload(buf, 3).unwrap();
+    assert_eq!(buf.len(), 3);
+}
+
+#[test]
+fn test_starts_with_ends_with() {
+    declare_stackptr!{
+        let frame: StackPtr<[u8]> = StackPtr::new(*b"GET /index.html\r\n");
+    }
+
+    assert!(frame.starts_with(b"GET "));
+    assert!(!frame.starts_with(b"POST "));
+    assert!(frame.starts_with(b""));
+    assert!(frame.ends_with(b"\r\n"));
+    assert!(!frame.ends_with(b"\n\n"));
+    assert!(!frame.ends_with(&[0; 32]));
+}
+
+#[test]
+fn test_raw_slice_round_trip() {
+    let drops = Cell::new(0);
+    declare_stackptr!{
+        let slice: StackPtr<[Counted]> = StackPtr::new([Counted::new(1, &drops), Counted::new(2, &drops)]);
+    }
+    let address = slice.as_ptr();
+
+    let (data, len) = StackPtr::into_raw_slice(slice);
+    assert_eq!((data as *const Counted, len), (address, 2));
+    assert_eq!(drops.get(), 0);
+
+    let slice: StackPtr<[Counted]> = unsafe { StackPtr::from_raw_slice(data, len) };
+    assert_eq!(slice.iter().map(|c| c.value).collect::<Vec<_>>(), vec![1, 2]);
+    drop(slice);
+    assert_eq!(drops.get(), 2);
+}
+
+#[test]
+fn test_addr_of_elem() {
+    declare_stackptr!{
+        let slice: StackPtr<[u32]> = StackPtr::new([1, 2, 3, 4]);
+    }
+
+    let addresses: Vec<usize> = (0..slice.len()).map(|i| slice.addr_of_elem(i) as usize).collect();
+    assert_eq!(addresses[0], slice.as_ptr() as usize);
+    for pair in addresses.windows(2) {
+        assert_eq!(pair[1] - pair[0], std::mem::size_of::<u32>());
+    }
+
+    // splitting moves nothing, so the tail still starts at the old address of the element after the separator
+    let (head, tail) = StackPtr::split_once(slice, |&x| x == 3);
+    assert_eq!(head.addr_of_elem(1) as usize, addresses[1]);
+    assert_eq!(tail.unwrap().addr_of_elem(0) as usize, addresses[3]);
+}
+
+#[test]
+fn test_partition_into() {
+    declare_stackptr!{
+        let numbers: StackPtr<[i32]> = StackPtr::new([5, 8, 1, 4, 7, 2]);
+    }
+
+    let mut even_buf = [const { std::mem::MaybeUninit::uninit() }; 4];
+    let mut odd_buf = [const { std::mem::MaybeUninit::uninit() }; 4];
+    let (evens, odds) = StackPtr::partition_into(numbers, |_, n| n % 2 == 0, &mut even_buf, &mut odd_buf);
+    assert_eq!(evens, [8, 4, 2]);
+    assert_eq!(odds, [5, 1, 7]);
+}
+
+#[test]
+fn test_partition_into_by_index_drops() {
+    let drops = Cell::new(0);
+    {
+        declare_stackptr!{
+            let slice: StackPtr<[Counted]> = StackPtr::new([
+                Counted::new(10, &drops),
+                Counted::new(11, &drops),
+                Counted::new(12, &drops),
+            ]);
+        }
+
+        let mut first_buf = [const { std::mem::MaybeUninit::uninit() }; 1];
+        let mut rest_buf = [const { std::mem::MaybeUninit::uninit() }; 2];
+        let (first, rest) = StackPtr::partition_into(slice, |i, _| i == 0, &mut first_buf, &mut rest_buf);
+        assert_eq!(first.iter().map(|c| c.value).collect::<Vec<_>>(), vec![10]);
+        assert_eq!(rest.iter().map(|c| c.value).collect::<Vec<_>>(), vec![11, 12]);
+        assert_eq!(drops.get(), 0);
+        drop(first);
+        assert_eq!(drops.get(), 1);
+    }
+    assert_eq!(drops.get(), 3);
+}
+
+#[test]
+fn test_partition_into_overflow() {
+    let drops = Cell::new(0);
+    let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+        declare_stackptr!{
+            let slice: StackPtr<[Counted]> = StackPtr::new([
+                Counted::new(1, &drops),
+                Counted::new(2, &drops),
+                Counted::new(3, &drops),
+                Counted::new(4, &drops),
+            ]);
+        }
+
+        let mut small_buf = [const { std::mem::MaybeUninit::uninit() }; 1];
+        let mut large_buf = [const { std::mem::MaybeUninit::uninit() }; 4];
+        StackPtr::partition_into(slice, |_, c| c.value < 3, &mut small_buf, &mut large_buf);
+    }));
+    assert!(result.is_err());
+    assert_eq!(drops.get(), 4);
+}
+
+#[test]
+fn test_overlaps() {
+    declare_stackptr!{
+        let slice: StackPtr<[u16]> = StackPtr::new([1, 2, 3, 4, 5, 6]);
+    }
+
+    assert!(StackPtr::overlaps(&slice[..3], &slice[2..]));
+    assert!(StackPtr::overlaps(&slice[2..], &slice[..3]));
+    assert!(StackPtr::overlaps(&slice[1..2], &slice[..]));
+    assert!(!StackPtr::overlaps(&slice[..3], &slice[3..]));
+    assert!(!StackPtr::overlaps(&slice[..], &slice[2..2]));
+
+    let (left, right) = slice.split_at(3);
+    assert!(!StackPtr::overlaps(left, right));
+
+    let (front, back) = StackPtr::split_once(slice, |&x| x == 3);
+    let back = back.unwrap();
+    assert!(!StackPtr::overlaps(&front, &back));
+    assert!(StackPtr::overlaps(&front, &front));
+}
+
+#[test]
+fn test_drop_and_zero() {
+    let mut secret = *b"hunter2";
+    StackPtr::drop_and_zero(unsafe { StackPtr::from_mut(&mut secret[..]) });
+    // the wipe is observed through the original storage, which outlives the StackPtr
+    assert_eq!(secret, [0; 7]);
+}
+
+#[test]
+fn test_drop_and_zero_drops_first() {
+    let drops = Cell::new(0);
+    let mut storage = std::mem::MaybeUninit::new([Counted::new(7, &drops), Counted::new(8, &drops)]);
+    StackPtr::drop_and_zero(unsafe { StackPtr::from_mut(&mut storage.assume_init_mut()[..]) });
+    assert_eq!(drops.get(), 2);
+
+    let size = std::mem::size_of_val(&storage);
+    let bytes = unsafe { std::slice::from_raw_parts(storage.as_ptr() as *const u8, size) };
+    assert!(bytes.iter().all(|&b| b == 0));
+}
@@ -1,6 +1,11 @@
 #[macro_use]
 extern crate stack_ptr;
 
+mod common;
+
+use std::cell::Cell;
+
+use common::DropCounter;
 use stack_ptr::StackPtr;
 
 #[test]
@@ -11,3 +16,559 @@ fn test_basic() {
 
     assert_eq!(&*slice, &[1,2,3,4,5]);
 }
+
+#[test]
+fn test_on_stack() {
+    use stack_ptr::StackAllocate;
+
+    let sum = [1,2,3].on_stack(|sp| sp.iter().sum::<i32>());
+    assert_eq!(sum, 6);
+
+    let s = String::from("hello").on_stack(|mut sp| {
+        sp.push_str(", world");
+        sp.clone()
+    });
+    assert_eq!(s, "hello, world");
+}
+
+#[test]
+fn test_from_value() {
+    use std::mem::MaybeUninit;
+
+    let mut slot = MaybeUninit::uninit();
+    let sp = StackPtr::from_value(&mut slot, String::from("hello"));
+    assert_eq!(&*sp, "hello");
+
+    let mut slot = MaybeUninit::uninit();
+    let sp = StackPtr::from_value(&mut slot, [1,2,3]);
+    let slice: StackPtr<[i32]> = unsafe { StackPtr::from_mut(StackPtr::into_mut(sp) as &mut [i32]) };
+    assert_eq!(&*slice, &[1,2,3]);
+}
+
+#[test]
+fn test_declare_sized_and_unsized() {
+    declare_stackptr!{
+        let sized = StackPtr::new(5);
+    }
+    declare_stackptr!{
+        let mut unsized_: StackPtr<[i32]> = StackPtr::new([3,2,1]);
+    }
+
+    unsized_.sort();
+    assert_eq!(*sized, 5);
+    assert_eq!(&*unsized_, &[1,2,3]);
+}
+
+#[test]
+fn test_raw_parts() {
+    declare_stackptr!{
+        let slice: StackPtr<[i32]> = StackPtr::new([1,2,3]);
+    }
+
+    let (ptr, lifetime) = StackPtr::into_raw_parts(slice);
+    let slice = unsafe { StackPtr::from_raw_parts(ptr, lifetime) };
+    assert_eq!(&*slice, &[1,2,3]);
+}
+
+#[test]
+fn test_drop_borrowing_earlier_data() {
+    use std::cell::RefCell;
+
+    struct Logger<'a>(&'a RefCell<Vec<&'static str>>, &'static str);
+
+    impl<'a> Drop for Logger<'a> {
+        fn drop(&mut self) {
+            self.0.borrow_mut().push(self.1);
+        }
+    }
+
+    struct Holder<'a, 'b> {
+        logger: StackPtr<'a, Logger<'b>>,
+    }
+
+    let log = RefCell::new(Vec::new());
+    {
+        declare_stackptr!{
+            let logger = StackPtr::new(Logger(&log, "pointee"));
+        }
+        let holder = Holder { logger };
+        assert_eq!(holder.logger.1, "pointee");
+        let _last = Logger(&log, "declared last");
+    }
+    assert_eq!(*log.borrow(), ["declared last", "pointee"]);
+}
+
+#[cfg(feature = "nightly")]
+#[test]
+fn test_metadata() {
+    declare_stackptr!{
+        let slice: StackPtr<[i32]> = StackPtr::new([1,2,3]);
+    }
+
+    assert_eq!(StackPtr::metadata(&slice), 3);
+
+    let meta = StackPtr::metadata(&slice);
+    let (ptr, _) = StackPtr::into_raw_parts(slice);
+    let slice: StackPtr<[i32]> = unsafe { StackPtr::from_raw_parts_with_meta(ptr as *mut (), meta) };
+    assert_eq!(&*slice, &[1,2,3]);
+}
+
+struct Logged<'a> {
+    id: u32,
+    log: &'a std::cell::RefCell<Vec<u32>>,
+    panic: bool,
+}
+
+impl<'a> Drop for Logged<'a> {
+    fn drop(&mut self) {
+        self.log.borrow_mut().push(self.id);
+        if self.panic {
+            panic!("drop of {} panicked", self.id);
+        }
+    }
+}
+
+#[test]
+fn test_array_drop_order() {
+    let log = std::cell::RefCell::new(Vec::new());
+    {
+        declare_stackptr!{
+            let a = StackPtr::new(Logged { id: 0, log: &log, panic: false });
+        }
+        declare_stackptr!{
+            let b = StackPtr::new(Logged { id: 1, log: &log, panic: false });
+        }
+        declare_stackptr!{
+            let c = StackPtr::new(Logged { id: 2, log: &log, panic: false });
+        }
+
+        let _arr = [a, b, c];
+    }
+    assert_eq!(*log.borrow(), [0,1,2]);
+}
+
+#[test]
+fn test_array_drop_panic() {
+    use std::panic::{self, AssertUnwindSafe};
+
+    let log = std::cell::RefCell::new(Vec::new());
+    let result = panic::catch_unwind(AssertUnwindSafe(|| {
+        declare_stackptr!{
+            let a = StackPtr::new(Logged { id: 0, log: &log, panic: false });
+        }
+        declare_stackptr!{
+            let b = StackPtr::new(Logged { id: 1, log: &log, panic: true });
+        }
+        declare_stackptr!{
+            let c = StackPtr::new(Logged { id: 2, log: &log, panic: false });
+        }
+
+        let _arr = [a, b, c];
+    }));
+
+    assert!(result.is_err());
+    assert_eq!(*log.borrow(), [0,1,2]);
+}
+
+#[test]
+fn test_from_pinned() {
+    use std::future::{self, Future};
+    use std::mem::ManuallyDrop;
+    use std::pin::{pin, Pin};
+    use std::task::{Context, Poll, Waker};
+
+    let pinned = pin!(ManuallyDrop::new(future::ready(3)));
+    let inner = unsafe { Pin::map_unchecked_mut(pinned, |md| &mut **md) };
+    let mut fut = unsafe { StackPtr::from_pinned(inner) };
+
+    let mut cx = Context::from_waker(Waker::noop());
+    assert_eq!(Pin::new(&mut *fut).poll(&mut cx), Poll::Ready(3));
+}
+
+#[test]
+fn test_coerce_stackptr() {
+    declare_stackptr!{
+        let array = StackPtr::new([1,2,3]);
+    }
+
+    let slice = coerce_stackptr!(array, [i32]);
+    assert_eq!(&*slice, &[1,2,3]);
+}
+
+#[test]
+fn test_as_dyn() {
+    use std::fmt::Debug;
+
+    declare_stackptr!{
+        let value = StackPtr::new(Some("x"));
+    }
+
+    let value: StackPtr<dyn Debug> = as_dyn!(value, Debug);
+    assert_eq!(format!("{:?}", value), "Some(\"x\")");
+
+    declare_stackptr!{
+        let iter = StackPtr::new(1..4);
+    }
+
+    let iter = as_dyn!(iter, Iterator<Item = i32> + Send);
+    assert_eq!(iter.sum::<i32>(), 6);
+}
+
+#[test]
+fn test_forget() {
+    let drops = Cell::new(0);
+    declare_stackptr!{
+        let counter = StackPtr::new(DropCounter(&drops));
+    }
+
+    StackPtr::forget(counter);
+    assert_eq!(drops.get(), 0);
+}
+
+#[test]
+fn test_layout() {
+    use std::alloc::Layout;
+    use std::fmt::Debug;
+
+    declare_stackptr!{
+        let bytes: StackPtr<[u8]> = StackPtr::new([0u8; 24]);
+    }
+    assert_eq!(StackPtr::layout(&bytes), Layout::new::<[u8; 24]>());
+    assert_eq!(StackPtr::layout(&bytes).size(), 24);
+
+    declare_stackptr!{
+        let value: StackPtr<dyn Debug> = StackPtr::new(0u64);
+    }
+    assert_eq!(StackPtr::layout(&value), Layout::new::<u64>());
+}
+
+#[test]
+fn test_assume_static() {
+    use std::mem::MaybeUninit;
+
+    fn keep(sp: StackPtr<'static, DropCounter<'static>>) -> StackPtr<'static, DropCounter<'static>> {
+        sp
+    }
+
+    let drops: &'static Cell<usize> = Box::leak(Box::new(Cell::new(0)));
+    let slot: &'static mut MaybeUninit<DropCounter<'static>> = Box::leak(Box::new(MaybeUninit::uninit()));
+
+    let sp = StackPtr::from_value(&mut *slot, DropCounter(drops));
+    let sp = keep(unsafe { StackPtr::assume_static(sp) });
+    assert_eq!(drops.get(), 0);
+
+    drop(sp);
+    assert_eq!(drops.get(), 1);
+}
+
+#[test]
+fn test_change_lifetime() {
+    let drops = Cell::new(0);
+    let mut pending = Vec::new();
+    {
+        declare_stackptr!{
+            let counter = StackPtr::new(DropCounter(&drops));
+        }
+        // `pending` outlives this block, so `counter` can only be pushed with a longer lifetime; it is cleared before the block ends
+        pending.push(unsafe { StackPtr::change_lifetime(counter) });
+        assert_eq!(drops.get(), 0);
+        pending.clear();
+    }
+    assert_eq!(drops.get(), 1);
+    assert!(pending.is_empty());
+}
+
+#[test]
+fn test_stack_field() {
+    use std::mem::ManuallyDrop;
+    use std::ptr;
+
+    struct Parent<'a> {
+        owned: DropCounter<'a>,
+        other: DropCounter<'a>,
+    }
+
+    let drops = Cell::new(0);
+    let mut parent = ManuallyDrop::new(Parent {
+        owned: DropCounter(&drops),
+        other: DropCounter(&drops),
+    });
+
+    let field = unsafe { stack_field!(&mut parent.owned) };
+    assert_eq!(drops.get(), 0);
+    drop(field);
+    assert_eq!(drops.get(), 1);
+
+    unsafe {
+        ptr::drop_in_place(&mut parent.other);
+    }
+    assert_eq!(drops.get(), 2);
+}
+
+#[test]
+fn test_non_null() {
+    use std::marker::PhantomData;
+
+    let drops = Cell::new(0);
+    declare_stackptr!{
+        let value = StackPtr::new((5, DropCounter(&drops)));
+    }
+
+    let ptr = StackPtr::as_non_null(&value);
+    assert_eq!(unsafe { ptr.as_ref().0 }, 5);
+    assert_eq!(ptr.as_ptr() as *const _, &*value as *const _);
+
+    let mut ptr = StackPtr::into_non_null(value);
+    unsafe {
+        ptr.as_mut().0 = 6;
+    }
+    assert_eq!(drops.get(), 0);
+
+    let value = unsafe { StackPtr::from_raw_parts(ptr.as_ptr(), PhantomData) };
+    assert_eq!(value.0, 6);
+    drop(value);
+    assert_eq!(drops.get(), 1);
+}
+
+#[test]
+fn test_declare_stackptrs() {
+    use std::fmt::Display;
+
+    let drops = Cell::new(0);
+    {
+        declare_stackptrs!{
+            let a = StackPtr::new(DropCounter(&drops));
+            let b: StackPtr<dyn Display> = StackPtr::new(2);
+            let mut c: StackPtr<[i32]> = StackPtr::new([3, 4]);
+        }
+
+        c.reverse();
+        assert_eq!(b.to_string(), "2");
+        assert_eq!(&*c, &[4, 3]);
+        assert_eq!(drops.get(), 0);
+    }
+    assert_eq!(drops.get(), 1);
+
+    declare_stackptrs!{
+        let x = StackPtr::new(1);
+        let y = StackPtr::new(1);
+    }
+    assert_eq!(*x, *y);
+    assert!(!std::ptr::eq(&*x, &*y));
+}
+
+#[test]
+fn test_manually_drop_round_trip() {
+    let drops = Cell::new(0);
+    declare_stackptr!{
+        let counter = StackPtr::new(DropCounter(&drops));
+    }
+
+    let wrapped = StackPtr::into_manually_drop(counter);
+    let counter = unsafe { StackPtr::from_manually_drop(wrapped) };
+    assert_eq!(drops.get(), 0);
+    drop(counter);
+    assert_eq!(drops.get(), 1);
+
+    declare_stackptr!{
+        let counter = StackPtr::new(DropCounter(&drops));
+    }
+    drop(StackPtr::into_manually_drop(counter));
+    assert_eq!(drops.get(), 1);
+}
+
+#[test]
+fn test_from_manually_drop_ref() {
+    use std::mem::ManuallyDrop;
+
+    let drops = Cell::new(0);
+    {
+        let mut counter = ManuallyDrop::new(DropCounter(&drops));
+        let sp = unsafe { StackPtr::from_manually_drop_ref(&mut counter) };
+        assert_eq!(drops.get(), 0);
+        drop(sp);
+        assert_eq!(drops.get(), 1);
+    }
+    assert_eq!(drops.get(), 1);
+}
+
+#[test]
+fn test_cell_round_trip() {
+    declare_stackptr!{
+        let counter = StackPtr::new(1u32);
+    }
+
+    let cell = StackPtr::into_cell(counter);
+    let shared = &cell;
+    unsafe {
+        *shared.get() += 1;
+        *shared.get() *= 10;
+    }
+
+    let counter = StackPtr::from_cell(cell);
+    assert_eq!(*counter, 20);
+}
+
+#[test]
+fn test_stack_build() {
+    use std::mem::MaybeUninit;
+
+    struct Config<'d> {
+        name: String,
+        retries: u32,
+        _counter: DropCounter<'d>,
+    }
+
+    fn build_config<'a, 'd>(slot: &'a mut MaybeUninit<Config<'d>>, name: &str, drops: &'d Cell<usize>) -> StackPtr<'a, Config<'d>> {
+        StackPtr::from_value(slot, Config {
+            name: name.to_string(),
+            retries: 3,
+            _counter: DropCounter(drops),
+        })
+    }
+
+    let drops = Cell::new(0);
+    {
+        stack_build!(let mut config = build_config, "server", &drops);
+        config.retries += 1;
+        assert_eq!(config.name, "server");
+        assert_eq!(config.retries, 4);
+        assert_eq!(drops.get(), 0);
+    }
+    assert_eq!(drops.get(), 1);
+}
+
+#[test]
+fn test_cmp_by() {
+    use std::cmp::Ordering;
+
+    struct Version {
+        name: &'static str,
+        number: u32,
+    }
+
+    declare_stackptr!{
+        let older = StackPtr::new(Version { name: "b", number: 1 });
+    }
+    declare_stackptr!{
+        let newer = StackPtr::new(Version { name: "a", number: 2 });
+    }
+
+    assert_eq!(StackPtr::cmp_by(&older, &newer, |a, b| a.number.cmp(&b.number)), Ordering::Less);
+    assert_eq!(StackPtr::cmp_by(&older, &newer, |a, b| a.name.cmp(b.name)), Ordering::Greater);
+    assert_eq!(StackPtr::cmp_by(&older, &older, |a, b| a.number.cmp(&b.number)), Ordering::Equal);
+}
+
+#[test]
+fn test_project_newtype() {
+    struct Wrapper<'a>(DropCounter<'a>);
+
+    let drops = Cell::new(0);
+    declare_stackptr!{
+        let wrapper = StackPtr::new(Wrapper(DropCounter(&drops)));
+    }
+
+    let inner = unsafe { StackPtr::project(wrapper, |w| &mut w.0) };
+    assert_eq!(drops.get(), 0);
+    drop(inner);
+    assert_eq!(drops.get(), 1);
+}
+
+#[test]
+fn test_project_field_with_copy_rest() {
+    struct Tagged {
+        tag: u32,
+        name: String,
+    }
+
+    declare_stackptr!{
+        let tagged = StackPtr::new(Tagged { tag: 7, name: String::from("seven") });
+    }
+    assert_eq!(tagged.tag, 7);
+
+    let mut name = unsafe { StackPtr::project(tagged, |t| &mut t.name) };
+    name.push('!');
+    assert_eq!(*name, "seven!");
+}
+
+struct Resource<'a> {
+    id: u32,
+    _counter: DropCounter<'a>,
+}
+
+fn open_resource(id: u32, drops: &Cell<usize>) -> Result<Resource<'_>, String> {
+    // the partially built resource is dropped before the error is returned
+    let resource = Resource { id, _counter: DropCounter(drops) };
+    if id == 0 {
+        return Err(format!("invalid id {}", id));
+    }
+    Ok(resource)
+}
+
+fn open_pair(first: u32, second: u32, drops: &Cell<usize>, log: &mut Vec<u32>) -> Result<u32, String> {
+    try_declare_stackptr!{
+        let a = StackPtr::try_new(open_resource(first, drops));
+    }
+    log.push(a.id);
+    try_declare_stackptr!{
+        let mut b: StackPtr<Resource> = StackPtr::try_new(open_resource(second, drops));
+    }
+    b.id += 1;
+    log.push(b.id);
+    Ok(a.id + b.id)
+}
+
+#[test]
+fn test_try_declare_stackptr_ok() {
+    let drops = Cell::new(0);
+    let mut log = vec![];
+    assert_eq!(open_pair(1, 2, &drops, &mut log), Ok(4));
+    assert_eq!(log, vec![1, 3]);
+    assert_eq!(drops.get(), 2);
+}
+
+#[test]
+fn test_try_declare_stackptr_err() {
+    let drops = Cell::new(0);
+    let mut log = vec![];
+    assert_eq!(open_pair(1, 0, &drops, &mut log), Err(String::from("invalid id 0")));
+    // the first resource is dropped on the way out, and the failed one was never stored
+    assert_eq!(log, vec![1]);
+    assert_eq!(drops.get(), 2);
+
+    assert!(open_pair(0, 1, &drops, &mut log).is_err());
+    assert_eq!(log, vec![1]);
+    assert_eq!(drops.get(), 3);
+}
+
+#[test]
+fn test_take_option() {
+    use std::fmt::Debug;
+    use std::mem::size_of;
+
+    assert_eq!(size_of::<Option<StackPtr<u64>>>(), size_of::<StackPtr<u64>>());
+    assert_eq!(size_of::<Option<StackPtr<[u64]>>>(), size_of::<StackPtr<[u64]>>());
+    assert_eq!(size_of::<Option<StackPtr<dyn Debug>>>(), size_of::<StackPtr<dyn Debug>>());
+
+    let drops = Cell::new(0);
+    declare_stackptr!{
+        let counter = StackPtr::new(DropCounter(&drops));
+    }
+    let mut slot = Some(counter);
+    let taken = StackPtr::take_option(&mut slot);
+    assert!(slot.is_none());
+    assert!(StackPtr::take_option(&mut slot).is_none());
+    assert_eq!(drops.get(), 0);
+    drop(taken);
+    assert_eq!(drops.get(), 1);
+}
+
+#[test]
+fn test_stackptr_is_pointer_sized() {
+    use std::fmt::Debug;
+    use std::mem::size_of;
+
+    assert_eq!(size_of::<StackPtr<u8>>(), size_of::<&mut u8>());
+    assert_eq!(size_of::<StackPtr<[u8]>>(), size_of::<&mut [u8]>());
+    assert_eq!(size_of::<Option<StackPtr<dyn Debug>>>(), size_of::<&mut dyn Debug>());
+}
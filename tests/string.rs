@@ -0,0 +1,39 @@
+extern crate stack_ptr;
+
+use std::fmt::Write;
+use std::mem::MaybeUninit;
+
+use stack_ptr::string::StackString;
+
+#[test]
+fn test_build_and_finalize() {
+    let mut buf = [MaybeUninit::uninit(); 16];
+    let mut string = StackString::new(&mut buf);
+    assert!(string.is_empty());
+
+    string.push_str("héllo").unwrap();
+    string.push(',').unwrap();
+    string.push(' ').unwrap();
+    write!(string, "{}!", 42).unwrap();
+    assert_eq!(string.as_str(), "héllo, 42!");
+    assert_eq!(string.len(), 11);
+    assert_eq!(string.remaining_capacity(), 5);
+
+    let sp = string.into_stackptr();
+    assert_eq!(&*sp, "héllo, 42!");
+}
+
+#[test]
+fn test_overflow() {
+    let mut buf = [MaybeUninit::uninit(); 4];
+    let mut string = StackString::new(&mut buf);
+
+    string.push_str("abc").unwrap();
+    assert_eq!(string.push_str("de"), Err("de"));
+    assert_eq!(string.push('é'), Err('é'));
+    assert_eq!(&*string, "abc");
+
+    string.push('d').unwrap();
+    assert_eq!(string.push('e'), Err('e'));
+    assert_eq!(&*string.into_stackptr(), "abcd");
+}
@@ -0,0 +1,49 @@
+#[macro_use]
+extern crate stack_ptr;
+
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::thread;
+
+use stack_ptr::thread::transfer_scoped;
+
+struct AtomicDropCounter<'a> {
+    value: Vec<i32>,
+    drops: &'a AtomicUsize,
+}
+
+impl<'a> Drop for AtomicDropCounter<'a> {
+    fn drop(&mut self) {
+        self.drops.fetch_add(1, Ordering::SeqCst);
+    }
+}
+
+#[test]
+fn test_transfer_and_return() {
+    let drops = AtomicUsize::new(0);
+    let main_thread = thread::current().id();
+    declare_stackptr!{
+        let sp = StackPtr::new(AtomicDropCounter { value: vec![1,2], drops: &drops });
+    }
+
+    let sp = transfer_scoped(sp, |mut sp| {
+        assert_ne!(thread::current().id(), main_thread);
+        sp.value.push(3);
+        sp
+    });
+
+    assert_eq!(sp.value, [1,2,3]);
+    assert_eq!(drops.load(Ordering::SeqCst), 0);
+    drop(sp);
+    assert_eq!(drops.load(Ordering::SeqCst), 1);
+}
+
+#[test]
+fn test_transfer_and_drop() {
+    let drops = AtomicUsize::new(0);
+    declare_stackptr!{
+        let sp = StackPtr::new(AtomicDropCounter { value: vec![], drops: &drops });
+    }
+
+    transfer_scoped(sp, drop);
+    assert_eq!(drops.load(Ordering::SeqCst), 1);
+}
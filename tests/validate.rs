@@ -0,0 +1,39 @@
+#[macro_use]
+extern crate stack_ptr;
+
+use std::ptr;
+
+use stack_ptr::StackPtr;
+
+#[test]
+fn test_validate_ok() {
+    declare_stackptr!{
+        let slice: StackPtr<[u32]> = StackPtr::new([1,2,3]);
+    }
+    assert_eq!(StackPtr::validate(&slice), Ok(()));
+    debug_assert!(StackPtr::validate(&slice).is_ok());
+
+    let value = 5u64;
+    assert_eq!(StackPtr::validate_raw(&value as *const u64), Ok(()));
+    assert_eq!(StackPtr::validate_raw_slice(slice.as_ptr(), slice.len()), Ok(()));
+    assert_eq!(StackPtr::validate_raw_slice(ptr::NonNull::<u32>::dangling().as_ptr(), 0), Ok(()));
+}
+
+#[cfg(debug_assertions)]
+#[test]
+fn test_validate_bad_raw_parts() {
+    assert_eq!(StackPtr::validate_raw(ptr::null::<u32>()), Err("pointer is null"));
+    assert_eq!(StackPtr::validate_raw(ptr::without_provenance::<u32>(0x1001)), Err("pointer is not aligned for the pointee type"));
+    assert_eq!(StackPtr::validate_raw(ptr::without_provenance::<[u8; 16]>(usize::MAX - 4)), Err("pointee extends past the end of the address space"));
+
+    assert_eq!(StackPtr::validate_raw_slice(ptr::null::<u8>(), 0), Err("pointer is null"));
+    assert_eq!(StackPtr::validate_raw_slice(ptr::without_provenance::<u16>(0x1001), 1), Err("pointer is not aligned for the pointee type"));
+    assert_eq!(StackPtr::validate_raw_slice(ptr::without_provenance::<u64>(0x1000), usize::MAX / 4), Err("pointee size overflows isize"));
+    assert_eq!(StackPtr::validate_raw_slice(ptr::without_provenance::<u8>(usize::MAX - 2), 8), Err("pointee extends past the end of the address space"));
+}
+
+#[cfg(not(debug_assertions))]
+#[test]
+fn test_validate_release_noop() {
+    assert_eq!(StackPtr::validate_raw(ptr::null::<u32>()), Ok(()));
+}
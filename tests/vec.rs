@@ -0,0 +1,152 @@
+#[macro_use]
+extern crate stack_ptr;
+
+mod common;
+
+use std::cell::Cell;
+use std::mem::MaybeUninit;
+
+use common::DropCounter;
+use stack_ptr::vec::{StackDynVec, StackStack, StackVec};
+
+#[test]
+fn test_push_to_capacity() {
+    let mut buf = [MaybeUninit::uninit(); 3];
+    let mut vec = StackVec::new(&mut buf);
+    assert_eq!(vec.capacity(), 3);
+    assert_eq!(vec.remaining_capacity(), 3);
+
+    vec.push(1).unwrap();
+    vec.push(2).unwrap();
+    assert_eq!(vec.remaining_capacity(), 1);
+    vec.push(3).unwrap();
+    assert_eq!(vec.remaining_capacity(), 0);
+
+    assert_eq!(vec.push(4), Err(4));
+    assert_eq!(&*vec, &[1,2,3]);
+}
+
+#[test]
+fn test_into_stackptr() {
+    let drops = Cell::new(0);
+    let mut buf = [const { MaybeUninit::uninit() }; 4];
+    let mut vec = StackVec::new(&mut buf);
+    vec.push(DropCounter(&drops)).unwrap();
+    vec.push(DropCounter(&drops)).unwrap();
+
+    let sp = vec.into_stackptr();
+    assert_eq!(sp.len(), 2);
+    assert_eq!(drops.get(), 0);
+    drop(sp);
+    assert_eq!(drops.get(), 2);
+}
+
+#[test]
+fn test_drop() {
+    let drops = Cell::new(0);
+    let mut buf = [const { MaybeUninit::uninit() }; 4];
+    let mut vec = StackVec::new(&mut buf);
+    vec.push(DropCounter(&drops)).unwrap();
+    vec.push(DropCounter(&drops)).unwrap();
+    vec.push(DropCounter(&drops)).unwrap();
+
+    drop(vec);
+    assert_eq!(drops.get(), 3);
+}
+
+#[test]
+fn test_dyn_vec_closures() {
+    let log = std::cell::RefCell::new(Vec::new());
+    let log = &log;
+    let name = String::from("third");
+
+    StackDynVec::<dyn FnMut(), 3>::scope::<128, _, _>(|mut fns| {
+        let mut count = 0;
+        stack_dyn_push!(fns, || log.borrow_mut().push("first".to_string())).unwrap_or_else(|_| panic!());
+        stack_dyn_push!(fns, move || { count += 1; log.borrow_mut().push(format!("second {}", count)) }).unwrap_or_else(|_| panic!());
+        stack_dyn_push!(fns, || log.borrow_mut().push(name.clone())).unwrap_or_else(|_| panic!());
+        assert_eq!(fns.len(), 3);
+        assert!(stack_dyn_push!(fns, || ()).is_err());
+
+        for mut f in fns {
+            f();
+            f();
+        }
+    });
+
+    assert_eq!(*log.borrow(), ["first", "first", "second 1", "second 2", "third", "third"]);
+}
+
+#[test]
+fn test_dyn_vec_out_of_space() {
+    StackDynVec::<dyn std::fmt::Debug, 4>::scope::<16, _, _>(|mut list| {
+        stack_dyn_push!(list, [0u8; 12]).unwrap();
+        assert_eq!(stack_dyn_push!(list, [1u32; 2]), Err([1u32; 2]));
+        stack_dyn_push!(list, 7u8).unwrap();
+        assert_eq!(list.len(), 2);
+    });
+}
+
+#[test]
+fn test_dyn_vec_drop() {
+    let drops = Cell::new(0);
+    StackDynVec::<dyn std::fmt::Debug, 4>::scope::<64, _, _>(|mut list| {
+        stack_dyn_push!(list, DropCounter(&drops)).unwrap();
+        stack_dyn_push!(list, (1u8, DropCounter(&drops))).unwrap();
+        assert_eq!(drops.get(), 0);
+    });
+    assert_eq!(drops.get(), 2);
+}
+
+#[cfg(feature = "nightly")]
+#[test]
+fn test_dyn_vec_push() {
+    let total = StackDynVec::<dyn Fn(i32) -> i32, 3>::scope::<64, _, _>(|mut fns| {
+        let k = 3;
+        fns.push(|x| x + 1).unwrap_or_else(|_| panic!());
+        fns.push(move |x| x * k).unwrap_or_else(|_| panic!());
+        fns.push(|x: i32| -x).unwrap_or_else(|_| panic!());
+        fns.into_iter().map(|f| f(2)).sum::<i32>()
+    });
+    assert_eq!(total, 3 + 6 - 2);
+}
+
+#[test]
+fn test_stack_push_pop() {
+    let mut buf = [const { MaybeUninit::uninit() }; 3];
+    let mut stack = StackStack::new(&mut buf);
+    assert_eq!(stack.capacity(), 3);
+    assert!(stack.is_empty());
+    assert_eq!(stack.peek(), None);
+    assert_eq!(stack.pop(), None);
+
+    stack.push(1).unwrap();
+    stack.push(2).unwrap();
+    stack.push(3).unwrap();
+    assert_eq!(stack.push(4), Err(4));
+    assert_eq!(stack.len(), 3);
+    assert_eq!(stack.peek(), Some(&3));
+
+    assert_eq!(stack.pop(), Some(3));
+    assert_eq!(stack.pop(), Some(2));
+    stack.push(5).unwrap();
+    assert_eq!(stack.pop(), Some(5));
+    assert_eq!(stack.pop(), Some(1));
+    assert_eq!(stack.pop(), None);
+}
+
+#[test]
+fn test_stack_drop_partially_filled() {
+    let drops = Cell::new(0);
+    {
+        let mut buf = [const { MaybeUninit::uninit() }; 4];
+        let mut stack = StackStack::new(&mut buf);
+        stack.push(DropCounter(&drops)).unwrap_or_else(|_| panic!("full"));
+        stack.push(DropCounter(&drops)).unwrap_or_else(|_| panic!("full"));
+        stack.push(DropCounter(&drops)).unwrap_or_else(|_| panic!("full"));
+
+        drop(stack.pop());
+        assert_eq!(drops.get(), 1);
+    }
+    assert_eq!(drops.get(), 3);
+}